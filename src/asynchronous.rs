@@ -0,0 +1,169 @@
+/*!
+Provides lookups that do not block an asynchronous runtime, see
+[`SearchPath::find_async`](../struct.SearchPath.html#method.find_async).
+*/
+
+use crate::{EntryKind, SearchPath};
+use futures_core::Stream;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use tokio::task;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A stream of the file system entities found in a search path, returned by
+/// [`SearchPath::matches_async`](struct.SearchPath.html#method.matches_async). Matches are
+/// produced in search path order; dropping the stream stops the search before the next check.
+///
+/// This type is only available with the `tokio` feature.
+///
+#[derive(Debug)]
+pub struct MatchStream {
+    receiver: mpsc::Receiver<PathBuf>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+/// The number of matches that may be found ahead of the consumer of a `MatchStream`.
+const STREAM_BUFFER: usize = 16;
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl SearchPath {
+    ///
+    /// Return the first file system entity, either file or directory, found in the search path,
+    /// or `None`, as [`find`](struct.SearchPath.html#method.find). The file system is checked on
+    /// the blocking thread pool of the current tokio runtime, so this does not block the
+    /// executor; the search uses a copy of this search path taken when the method is called.
+    ///
+    /// This method is only available with the `tokio` feature, and panics if called outside of
+    /// the context of a tokio runtime.
+    ///
+    /// ```rust
+    /// use search_path::SearchPath;
+    /// use std::path::Path;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let search_path = SearchPath::new_or_default("PATH");
+    /// let shell = search_path.find_async(Path::new("sh")).await;
+    /// # });
+    /// ```
+    ///
+    pub async fn find_async(&self, file_name: &Path) -> Option<PathBuf> {
+        self.spawn_lookup(file_name, |search_path, file_name| {
+            search_path.find(file_name)
+        })
+        .await
+    }
+
+    ///
+    /// Return all the file system entities, either file or directory, found in the search path,
+    /// as [`find_all`](struct.SearchPath.html#method.find_all), without blocking the executor as
+    /// described for [`find_async`](struct.SearchPath.html#method.find_async).
+    ///
+    /// This method is only available with the `tokio` feature.
+    ///
+    pub async fn find_all_async(&self, file_name: &Path) -> Vec<PathBuf> {
+        self.spawn_lookup(file_name, |search_path, file_name| {
+            search_path.find_all(file_name)
+        })
+        .await
+    }
+
+    ///
+    /// Return the first _file_ found in the search path, or `None`, as
+    /// [`find_file`](struct.SearchPath.html#method.find_file), without blocking the executor as
+    /// described for [`find_async`](struct.SearchPath.html#method.find_async).
+    ///
+    /// This method is only available with the `tokio` feature.
+    ///
+    pub async fn find_file_async(&self, file_name: &Path) -> Option<PathBuf> {
+        self.spawn_lookup(file_name, |search_path, file_name| {
+            search_path.find_file(file_name)
+        })
+        .await
+    }
+
+    ///
+    /// Return the first _executable file_ found in the search path, or `None`, as
+    /// [`find_executable`](struct.SearchPath.html#method.find_executable), without blocking the
+    /// executor as described for [`find_async`](struct.SearchPath.html#method.find_async).
+    ///
+    /// This method is only available with the `tokio` feature.
+    ///
+    pub async fn find_executable_async(&self, file_name: &Path) -> Option<PathBuf> {
+        self.spawn_lookup(file_name, |search_path, file_name| {
+            search_path.find_executable(file_name)
+        })
+        .await
+    }
+
+    ///
+    /// Return a stream of all the file system entities, either file or directory, found in the
+    /// search path, in the same order as [`matches`](struct.SearchPath.html#method.matches). Each
+    /// directory is checked on the blocking thread pool of the current tokio runtime, at most a
+    /// few matches ahead of the consumer of the stream.
+    ///
+    /// This method is only available with the `tokio` feature, and panics if called outside of
+    /// the context of a tokio runtime.
+    ///
+    pub fn matches_async(&self, file_name: &Path) -> MatchStream {
+        self.matches_async_of_kind(file_name, EntryKind::Any)
+    }
+
+    ///
+    /// Return a stream of all the file system entities of `kind` found in the search path, see
+    /// [`matches_async`](struct.SearchPath.html#method.matches_async) for details.
+    ///
+    /// This method is only available with the `tokio` feature.
+    ///
+    pub fn matches_async_of_kind(&self, file_name: &Path, kind: EntryKind) -> MatchStream {
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        let search_path = self.clone();
+        let file_name = file_name.to_path_buf();
+        // The handle is not needed, the stream ends when the sender is dropped.
+        drop(task::spawn_blocking(move || {
+            for path in search_path.matches_something(&file_name, kind) {
+                if sender.blocking_send(path).is_err() {
+                    break;
+                }
+            }
+        }));
+        MatchStream { receiver }
+    }
+
+    async fn spawn_lookup<T, F>(&self, file_name: &Path, lookup: F) -> T
+    where
+        T: Default + Send + 'static,
+        F: FnOnce(&SearchPath, &Path) -> T + Send + 'static,
+    {
+        let search_path = self.clone();
+        let file_name = file_name.to_path_buf();
+        match task::spawn_blocking(move || lookup(&search_path, &file_name)).await {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => panic::resume_unwind(e.into_panic()),
+            // The runtime is shutting down and the lookup was never run.
+            Err(_) => T::default(),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Stream for MatchStream {
+    type Item = PathBuf;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
@@ -0,0 +1,412 @@
+/*!
+Provides a caching layer over a search path that reads each directory listing once and answers
+lookups from memory, see [`CachedSearchPath`](../struct.CachedSearchPath.html).
+*/
+
+use crate::{Error, FileSystem, FileType, Metadata, OsFileSystem, SearchPath, Walk, WalkOptions};
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The set of options used by a [`CachedSearchPath`](struct.CachedSearchPath.html). By default
+/// cached listings do not expire, but each is revalidated whenever it is used by comparing the
+/// modification time of the directory with the time recorded when it was read.
+///
+/// ```rust
+/// use search_path::CacheOptions;
+/// use std::time::Duration;
+///
+/// let options = CacheOptions::default()
+///     .with_ttl(Duration::from_secs(30))
+///     .with_revalidate(false);
+/// ```
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheOptions {
+    ttl: Option<Duration>,
+    revalidate: bool,
+}
+
+///
+/// A search path that caches the listing of each directory it searches, so that repeated lookups
+/// are answered from memory rather than by checking each candidate on disk. The cache may be
+/// shared between threads.
+///
+/// Listings are invalidated explicitly, using [`invalidate`](struct.CachedSearchPath.html#method.invalidate)
+/// or [`invalidate_all`](struct.CachedSearchPath.html#method.invalidate_all), when they are older
+/// than the time-to-live in the [`CacheOptions`](struct.CacheOptions.html), or, if revalidation is
+/// enabled, when the modification time of the directory changes. Revalidation still requires one
+/// check of each directory per lookup, but not of each candidate within it. The metadata of every
+/// entry, including its permissions, is read along with the listing, so that checks such as
+/// whether a file is executable are also answered from memory.
+///
+/// ```rust
+/// use search_path::{CachedSearchPath, SearchPath};
+/// use std::path::Path;
+///
+/// let cached = CachedSearchPath::from(SearchPath::new_or_default("PATH"));
+/// for name in &["ls", "cat", "ls"] {
+///     let found = cached.find_file(Path::new(name));
+/// }
+/// ```
+///
+#[derive(Debug)]
+pub struct CachedSearchPath {
+    search_path: SearchPath,
+    options: CacheOptions,
+    listings: Mutex<HashMap<PathBuf, Arc<Listing>>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The cached listing of a single directory, a directory that does not exist is cached with no
+/// entries.
+///
+#[derive(Debug)]
+struct Listing {
+    canonical: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    read_at: Instant,
+    entries: Vec<(String, PathBuf, Info)>,
+    by_name: HashMap<OsString, usize>,
+}
+
+///
+/// The file system as seen through the cached listings of a `CachedSearchPath`, so that lookups
+/// share the implementation of `SearchPath`. The metadata of an entry is that read along with the
+/// listing of its parent directory.
+///
+#[derive(Debug)]
+struct Listed<'a>(&'a CachedSearchPath);
+
+///
+/// What is known about a single entry in a listing, `metadata` follows symbolic links and is
+/// `None` if the entry, or the target of a symbolic link, is missing.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Info {
+    symlink: bool,
+    metadata: Option<Metadata>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Default for CacheOptions {
+    fn default() -> Self {
+        Self {
+            ttl: None,
+            revalidate: true,
+        }
+    }
+}
+
+impl CacheOptions {
+    ///
+    /// Return a copy of these options with the time-to-live of each cached listing set to `ttl`.
+    ///
+    pub fn with_ttl(self, ttl: Duration) -> Self {
+        Self {
+            ttl: Some(ttl),
+            ..self
+        }
+    }
+
+    ///
+    /// Return the time-to-live of each cached listing, or `None` if listings do not expire.
+    ///
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    ///
+    /// Return a copy of these options with the revalidation of cached listings, against the
+    /// modification time of each directory, set to `revalidate`.
+    ///
+    pub fn with_revalidate(self, revalidate: bool) -> Self {
+        Self { revalidate, ..self }
+    }
+
+    ///
+    /// Return `true` if cached listings are revalidated each time they are used, else `false`.
+    ///
+    pub fn revalidate(&self) -> bool {
+        self.revalidate
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl From<SearchPath> for CachedSearchPath {
+    fn from(search_path: SearchPath) -> Self {
+        Self::new(search_path, CacheOptions::default())
+    }
+}
+
+impl CachedSearchPath {
+    ///
+    /// Construct a new, empty, cache for `search_path` using `options`.
+    ///
+    pub fn new(search_path: SearchPath, options: CacheOptions) -> Self {
+        Self {
+            search_path,
+            options,
+            listings: Default::default(),
+        }
+    }
+
+    ///
+    /// Return the search path this cache wraps.
+    ///
+    pub fn search_path(&self) -> &SearchPath {
+        &self.search_path
+    }
+
+    ///
+    /// Return the options used by this cache.
+    ///
+    pub fn options(&self) -> &CacheOptions {
+        &self.options
+    }
+
+    ///
+    /// Return the first file system entity, either file or directory, found in the search path,
+    /// or `None`, as [`SearchPath::find`](struct.SearchPath.html#method.find).
+    ///
+    pub fn find(&self, file_name: &Path) -> Option<PathBuf> {
+        self.search_path.find_in(&Listed(self), file_name)
+    }
+
+    ///
+    /// Return all the file system entities, either file or directory, found in the search path,
+    /// as [`SearchPath::find_all`](struct.SearchPath.html#method.find_all).
+    ///
+    pub fn find_all(&self, file_name: &Path) -> Vec<PathBuf> {
+        self.search_path.find_all_in(&Listed(self), file_name)
+    }
+
+    ///
+    /// Return the first file found in the search path, or `None`, as
+    /// [`SearchPath::find_file`](struct.SearchPath.html#method.find_file).
+    ///
+    pub fn find_file(&self, file_name: &Path) -> Option<PathBuf> {
+        self.search_path.find_file_in(&Listed(self), file_name)
+    }
+
+    ///
+    /// Return the first directory found in the search path, or `None`, as
+    /// [`SearchPath::find_directory`](struct.SearchPath.html#method.find_directory).
+    ///
+    pub fn find_directory(&self, file_name: &Path) -> Option<PathBuf> {
+        self.search_path.find_directory_in(&Listed(self), file_name)
+    }
+
+    ///
+    /// Return the first executable file found in the search path, or `None`, as
+    /// [`SearchPath::find_executable`](struct.SearchPath.html#method.find_executable).
+    ///
+    pub fn find_executable(&self, file_name: &Path) -> Option<PathBuf> {
+        self.search_path
+            .find_executable_in(&Listed(self), file_name)
+    }
+
+    ///
+    /// Return the first file system entity that matches the glob `pattern` in the search path, or
+    /// `None`, as [`SearchPath::find_glob`](struct.SearchPath.html#method.find_glob).
+    ///
+    pub fn find_glob(&self, pattern: &str) -> Result<Option<PathBuf>, Error> {
+        self.search_path.find_glob_in(&Listed(self), pattern)
+    }
+
+    ///
+    /// Return all the file system entities that match the glob `pattern` in the search path, as
+    /// [`SearchPath::find_all_glob`](struct.SearchPath.html#method.find_all_glob).
+    ///
+    pub fn find_all_glob(&self, pattern: &str) -> Result<Vec<PathBuf>, Error> {
+        self.search_path.find_all_glob_in(&Listed(self), pattern)
+    }
+
+    ///
+    /// Discard the cached listing of the directory `path`, and of any directory below it, for
+    /// example all the directories searched for a recursive entry.
+    ///
+    pub fn invalidate(&self, path: &Path) {
+        self.lock().retain(|dir, _| !dir.starts_with(path))
+    }
+
+    ///
+    /// Discard all cached listings.
+    ///
+    pub fn invalidate_all(&self) {
+        self.lock().clear()
+    }
+
+    ///
+    /// Return all the directories searched for the entries of the search path, as
+    /// `SearchPath::directories` but using cached listings.
+    ///
+    pub(crate) fn directories(&self) -> Vec<PathBuf> {
+        self.search_path.directories(&Listed(self)).collect()
+    }
+
+    ///
+    /// Return a directory and all its sub-directories, as `Walk` with the default options but
+    /// using cached listings.
+    ///
+    pub(crate) fn walk(&self, root: &Path) -> Vec<PathBuf> {
+        Walk::new(&Listed(self), root, &WalkOptions::default()).collect()
+    }
+
+    fn info(&self, path: &Path) -> Option<Info> {
+        let parent = match path.parent()? {
+            parent if parent.as_os_str().is_empty() => Path::new(crate::CURRENT_DIR_PATH),
+            parent => parent,
+        };
+        let listing = self.listing(parent);
+        let i = *listing.by_name.get(path.file_name()?)?;
+        Some(listing.entries[i].2.clone())
+    }
+
+    fn listing(&self, dir: &Path) -> Arc<Listing> {
+        // The lock is released before revalidating, so lookups do not wait on each other's checks.
+        let cached = self.lock().get(dir).cloned();
+        if let Some(listing) = cached {
+            if self.is_fresh(dir, &listing) {
+                return listing;
+            }
+        }
+        // The directory is read without holding the lock, so a concurrent lookup may read it too.
+        let listing = Arc::new(Listing::read(&OsFileSystem, dir));
+        let _ = self.lock().insert(dir.to_path_buf(), listing.clone());
+        listing
+    }
+
+    fn is_fresh(&self, dir: &Path, listing: &Listing) -> bool {
+        self.options
+            .ttl
+            .map(|ttl| listing.read_at.elapsed() < ttl)
+            .unwrap_or(true)
+            && (!self.options.revalidate
+                || crate::modified_time(&OsFileSystem, dir) == listing.modified)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<PathBuf, Arc<Listing>>> {
+        // The map is always left consistent, so a panic in another thread does not affect it.
+        self.listings.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl FileSystem for Listed<'_> {
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        if path.file_name().is_none() {
+            // Paths ending in `..` cannot be found in a listing.
+            return OsFileSystem.metadata(path);
+        }
+        self.0
+            .info(path)
+            .and_then(|info| info.metadata)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata> {
+        match self.0.info(path) {
+            Some(Info { symlink: true, .. }) => Ok(Metadata::new(FileType::Symlink)),
+            _ => self.metadata(path),
+        }
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(self
+            .0
+            .listing(path)
+            .entries
+            .iter()
+            .map(|(_, path, _)| path.clone())
+            .collect())
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read>> {
+        OsFileSystem.open(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.0
+            .listing(path)
+            .canonical
+            .clone()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Listing {
+    fn read<F: FileSystem + ?Sized>(file_system: &F, dir: &Path) -> Self {
+        // The time is recorded before reading so that any concurrent change is revalidated.
+        let modified = crate::modified_time(file_system, dir);
+        let read_at = Instant::now();
+        let mut entries: Vec<(String, PathBuf, Info)> = match file_system.read_dir(dir) {
+            Ok(paths) => paths
+                .into_iter()
+                .filter_map(|path| {
+                    let name = path.file_name()?.to_string_lossy().to_string();
+                    let info = Info::new(file_system, &path);
+                    Some((name, path, info))
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        entries.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        let by_name = entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, path, _))| {
+                path.file_name().map(|name| (OsStr::to_os_string(name), i))
+            })
+            .collect();
+        Self {
+            canonical: file_system.canonicalize(dir).ok(),
+            modified,
+            read_at,
+            entries,
+            by_name,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Info {
+    fn new<F: FileSystem + ?Sized>(file_system: &F, path: &Path) -> Self {
+        // The full metadata, including permissions, is kept so that executable and other checks
+        // made through the listing give the same answers as on disk.
+        match file_system.metadata(path) {
+            Ok(metadata) => Self {
+                symlink: metadata.is_symlink(),
+                metadata: Some(metadata),
+            },
+            // An entry that is listed but cannot be read is usually a broken symbolic link.
+            Err(_) => Self {
+                symlink: matches!(
+                    file_system.symlink_metadata(path),
+                    Ok(metadata) if metadata.file_type() == FileType::Symlink
+                ),
+                metadata: None,
+            },
+        }
+    }
+}
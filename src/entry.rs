@@ -0,0 +1,179 @@
+/*!
+Provides the type of a single entry in a search path, along with the kpathsea-style flags that may
+be attached to it, see [`ParseOptions::with_kpathsea`](../struct.ParseOptions.html#method.with_kpathsea).
+*/
+
+use crate::{FileSystem, Index, Walk, WalkOptions};
+use std::ffi::{OsStr, OsString};
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A single entry in a search path. As well as the path itself an entry records whether the
+/// _find_ methods also search all of its sub-directories, written as a trailing `//` in kpathsea
+/// syntax, and whether it may only be searched using an index rather than by reading the disk,
+/// written as a leading `!!`.
+///
+/// ```rust
+/// use search_path::{Entry, SearchPath};
+///
+/// let search_path = SearchPath::from(vec![
+///     Entry::new("/usr/share/texmf").with_recursive(true),
+///     Entry::new("./styles"),
+/// ]);
+/// assert_eq!(search_path.to_string(), "/usr/share/texmf//:./styles");
+/// ```
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Entry {
+    path: PathBuf,
+    recursive: bool,
+    index_only: bool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+const RECURSIVE_SUFFIX: &str = "//";
+
+const INDEX_ONLY_PREFIX: &str = "!!";
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_kpathsea().to_string_lossy())
+    }
+}
+
+impl From<PathBuf> for Entry {
+    fn from(path: PathBuf) -> Self {
+        Self {
+            path,
+            recursive: false,
+            index_only: false,
+        }
+    }
+}
+
+impl From<Entry> for PathBuf {
+    fn from(entry: Entry) -> Self {
+        entry.path
+    }
+}
+
+impl Entry {
+    ///
+    /// Construct a new entry for `path`, which is neither recursive nor index-only.
+    ///
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self::from(path.into())
+    }
+
+    ///
+    /// Return the path of this entry, without any kpathsea markers.
+    ///
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    ///
+    /// Return a copy of this entry with the searching of all sub-directories set to `recursive`.
+    ///
+    pub fn with_recursive(self, recursive: bool) -> Self {
+        Self { recursive, ..self }
+    }
+
+    ///
+    /// Return `true` if the _find_ methods search all the sub-directories of this entry, else
+    /// `false`.
+    ///
+    pub fn is_recursive(&self) -> bool {
+        self.recursive
+    }
+
+    ///
+    /// Return a copy of this entry with the restriction to index-only searches set to
+    /// `index_only`.
+    ///
+    pub fn with_index_only(self, index_only: bool) -> Self {
+        Self { index_only, ..self }
+    }
+
+    ///
+    /// Return `true` if this entry may only be searched using an index, and never by reading the
+    /// disk, else `false`. If the search path has no index covering this entry it is not searched,
+    /// see [`SearchPath::set_index`](struct.SearchPath.html#method.set_index).
+    ///
+    pub fn is_index_only(&self) -> bool {
+        self.index_only
+    }
+
+    ///
+    /// Parse a single kpathsea-style component, stripping any leading `!!` and trailing `//`.
+    ///
+    pub(crate) fn parse_kpathsea(s: &OsStr) -> Self {
+        let s = s.as_encoded_bytes();
+        let (s, index_only) = match s.strip_prefix(INDEX_ONLY_PREFIX.as_bytes()) {
+            Some(rest) => (rest, true),
+            None => (s, false),
+        };
+        let (s, recursive) = match s.strip_suffix(RECURSIVE_SUFFIX.as_bytes()) {
+            // A bare `//` denotes the root directory and all its sub-directories.
+            Some(b"") => (&b"/"[..], true),
+            Some(rest) => (rest, true),
+            None => (s, false),
+        };
+        Self {
+            path: PathBuf::from(crate::encoded_os_str(s)),
+            recursive,
+            index_only,
+        }
+    }
+
+    ///
+    /// Return this entry in kpathsea syntax, the markers are only present if the corresponding
+    /// flag is set.
+    ///
+    pub(crate) fn to_kpathsea(&self) -> OsString {
+        let mut s = OsString::new();
+        if self.index_only {
+            s.push(INDEX_ONLY_PREFIX);
+        }
+        s.push(&self.path);
+        if self.recursive {
+            s.push(RECURSIVE_SUFFIX);
+        }
+        s
+    }
+
+    ///
+    /// Return the directories the _find_ methods search for this entry. If `index` covers this
+    /// entry these are the directories it lists, limited to those containing one of `names` if
+    /// present; otherwise the path itself and, if recursive, each of its sub-directories within
+    /// `file_system` breadth-first.
+    ///
+    pub(crate) fn directories<'a, F: FileSystem + ?Sized>(
+        &self,
+        file_system: &'a F,
+        index: Option<&Index>,
+        names: Option<&[PathBuf]>,
+    ) -> Box<dyn Iterator<Item = PathBuf> + 'a> {
+        if let Some(directories) = index.and_then(|index| index.directories_for(self, names)) {
+            Box::new(directories.into_iter())
+        } else if self.index_only {
+            Box::new(std::iter::empty())
+        } else if self.recursive {
+            Box::new(Walk::new(file_system, &self.path, &WalkOptions::default()))
+        } else {
+            Box::new(std::iter::once(self.path.clone()))
+        }
+    }
+}
@@ -0,0 +1,158 @@
+/*!
+Provides the error type returned by the fallible operations of this crate.
+*/
+
+use std::ffi::OsString;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The error type for all fallible operations in this crate.
+///
+#[derive(Debug)]
+pub enum Error {
+    /// The named environment variable is not present in the current process environment.
+    NotPresent {
+        /// The name of the environment variable.
+        name: String,
+    },
+    /// The named environment variable is present, but its value is not valid Unicode.
+    NotUnicode {
+        /// The name of the environment variable.
+        name: String,
+        /// The value of the environment variable.
+        value: OsString,
+    },
+    /// An entry in the search path cannot be represented, or is otherwise invalid.
+    InvalidEntry {
+        /// The offending entry.
+        entry: PathBuf,
+        /// A description of why the entry is invalid.
+        reason: String,
+    },
+    /// A pattern used to find entries is invalid.
+    InvalidPattern {
+        /// The offending pattern.
+        pattern: String,
+        /// A description of why the pattern is invalid.
+        reason: String,
+    },
+    /// An I/O error occurred while accessing the file system.
+    Io {
+        /// The path being accessed when the error occurred.
+        path: PathBuf,
+        /// The underlying I/O error.
+        source: io::Error,
+    },
+    /// The deadline of a lookup ran out, see [`LookupOptions`](struct.LookupOptions.html).
+    Timeout {
+        /// The entry in the search path being searched when the lookup ran out.
+        entry: PathBuf,
+        /// The number of candidates checked by the lookup.
+        probes: usize,
+        /// The time taken by the lookup.
+        elapsed: Duration,
+    },
+    /// The maximum number of probes of a lookup was reached, see
+    /// [`LookupOptions`](struct.LookupOptions.html).
+    ProbeLimit {
+        /// The entry in the search path being searched when the lookup ran out.
+        entry: PathBuf,
+        /// The number of probes made by the lookup.
+        probes: usize,
+    },
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::NotPresent { name } => {
+                write!(f, "environment variable `{}` is not present", name)
+            }
+            Error::NotUnicode { name, .. } => {
+                write!(f, "environment variable `{}` is not valid Unicode", name)
+            }
+            Error::InvalidEntry { entry, reason } => {
+                write!(f, "invalid search path entry {:?}: {}", entry, reason)
+            }
+            Error::InvalidPattern { pattern, reason } => {
+                write!(f, "invalid pattern {:?}: {}", pattern, reason)
+            }
+            Error::Io { path, source } => {
+                write!(f, "I/O error accessing {:?}: {}", path, source)
+            }
+            Error::Timeout {
+                entry,
+                probes,
+                elapsed,
+            } => {
+                write!(
+                    f,
+                    "lookup timed out searching {:?} after {} probes in {:?}",
+                    entry, probes, elapsed
+                )
+            }
+            Error::ProbeLimit { entry, probes } => {
+                write!(
+                    f,
+                    "lookup reached its limit of {} probes searching {:?}",
+                    probes, entry
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    pub(crate) fn from_var_error(name: &str, e: std::env::VarError) -> Self {
+        match e {
+            std::env::VarError::NotPresent => Error::NotPresent {
+                name: name.to_string(),
+            },
+            std::env::VarError::NotUnicode(value) => Error::NotUnicode {
+                name: name.to_string(),
+                value,
+            },
+        }
+    }
+
+    pub(crate) fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        Error::Io {
+            path: path.into(),
+            source,
+        }
+    }
+
+    pub(crate) fn invalid_entry(entry: impl Into<PathBuf>, reason: impl Into<String>) -> Self {
+        Error::InvalidEntry {
+            entry: entry.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub(crate) fn invalid_pattern(pattern: &str, reason: impl Into<String>) -> Self {
+        Error::InvalidPattern {
+            pattern: pattern.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
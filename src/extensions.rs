@@ -0,0 +1,184 @@
+/*!
+Provides the list of file extensions used to resolve a bare command name, in the manner of the
+Windows `PATHEXT` environment variable.
+*/
+
+use crate::Error;
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An ordered list of file extensions, such as `".exe"` or `".sh"`, that are appended to a name
+/// when searching, see [`SearchPath::set_extensions`](struct.SearchPath.html#method.set_extensions).
+///
+/// When parsed from a string, extensions are always separated by the `';'` character regardless
+/// of platform, as is the case for the `PATHEXT` environment variable, and empty components are
+/// ignored. A leading `'.'` is added to any extension that does not already have one.
+///
+/// ```rust
+/// use search_path::Extensions;
+///
+/// let extensions: Extensions = ".COM;.EXE;BAT".into();
+/// assert_eq!(extensions.len(), 3);
+/// assert_eq!(extensions.iter().last().unwrap(), ".BAT");
+/// ```
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Extensions {
+    extensions: Vec<OsString>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+const EXTENSION_SEPARATOR_CHAR: char = ';';
+
+const EXTENSION_PREFIX_CHAR: char = '.';
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl From<Vec<&str>> for Extensions {
+    fn from(vs: Vec<&str>) -> Self {
+        Self {
+            extensions: vs
+                .iter()
+                .map(|e| e.trim())
+                .filter(|e| !e.is_empty())
+                .map(|e| {
+                    if e.starts_with(EXTENSION_PREFIX_CHAR) {
+                        OsString::from(e)
+                    } else {
+                        OsString::from(format!("{}{}", EXTENSION_PREFIX_CHAR, e))
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<String> for Extensions {
+    fn from(s: String) -> Self {
+        Self::from(s.as_str())
+    }
+}
+
+impl From<&str> for Extensions {
+    fn from(s: &str) -> Self {
+        Self::from(s.split(EXTENSION_SEPARATOR_CHAR).collect::<Vec<&str>>())
+    }
+}
+
+impl From<&OsStr> for Extensions {
+    fn from(s: &OsStr) -> Self {
+        Self {
+            extensions: s
+                .as_encoded_bytes()
+                .split(|b| *b == EXTENSION_SEPARATOR_CHAR as u8)
+                .map(<[u8]>::trim_ascii)
+                .filter(|e| !e.is_empty())
+                .map(|e| {
+                    let mut extension = OsString::new();
+                    if e[0] != EXTENSION_PREFIX_CHAR as u8 {
+                        extension.push(EXTENSION_PREFIX_CHAR.to_string());
+                    }
+                    extension.push(crate::encoded_os_str(e));
+                    extension
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<OsString> for Extensions {
+    fn from(s: OsString) -> Self {
+        Self::from(s.as_os_str())
+    }
+}
+
+impl Extensions {
+    ///
+    /// Construct a new extension list by parsing the environment variable named `env_var`. The
+    /// value is not required to be valid Unicode.
+    ///
+    /// If the environment variable is not present this function returns an
+    /// [`Error`](enum.Error.html) describing the failure.
+    ///
+    pub fn new(env_var: &str) -> Result<Self, Error> {
+        match env::var_os(env_var) {
+            Some(extensions) => Ok(Self::from(extensions)),
+            None => Err(Error::NotPresent {
+                name: env_var.to_string(),
+            }),
+        }
+    }
+
+    ///
+    /// Construct a new extension list by parsing the environment variable named `PATHEXT`.
+    ///
+    pub fn pathext() -> Result<Self, Error> {
+        Self::new("PATHEXT")
+    }
+
+    ///
+    /// Construct a new extension list by parsing the environment variable named `env_var`. If
+    /// the environment variable is not present this function returns the default value provided
+    /// instead.
+    ///
+    pub fn new_or<T: Into<Extensions>>(env_var: &str, default: T) -> Self {
+        match Self::new(env_var) {
+            Ok(extensions) => extensions,
+            Err(_) => default.into(),
+        }
+    }
+
+    ///
+    /// Construct a new extension list by parsing the environment variable named `env_var`. If
+    /// the environment variable is not present this function returns an empty list.
+    ///
+    pub fn new_or_default(env_var: &str) -> Self {
+        Self::new_or(env_var, Extensions::default())
+    }
+
+    ///
+    /// Return `true` if this list contains no extensions, else `false`.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.extensions.is_empty()
+    }
+
+    ///
+    /// Return the number of extensions in this list.
+    ///
+    pub fn len(&self) -> usize {
+        self.extensions.len()
+    }
+
+    ///
+    /// Return an iterator over all the extensions in this list, in order.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = &OsString> {
+        self.extensions.iter()
+    }
+
+    ///
+    /// Return the candidate names for `file_name`; this is `file_name` itself followed by
+    /// `file_name` with each extension appended, in order.
+    ///
+    pub(crate) fn candidates(&self, file_name: &Path) -> Vec<PathBuf> {
+        let mut candidates = vec![file_name.to_path_buf()];
+        candidates.extend(self.extensions.iter().map(|extension| {
+            let mut name = file_name.as_os_str().to_os_string();
+            name.push(extension);
+            PathBuf::from(name)
+        }));
+        candidates
+    }
+}
@@ -0,0 +1,928 @@
+/*!
+Provides the `FileSystem` trait, used to search trees other than the local disk, see
+[`SearchPath::find_in`](../struct.SearchPath.html#method.find_in).
+*/
+
+#[cfg(feature = "regex")]
+use crate::RegexMatch;
+use crate::{
+    EntryKind, Error, Glob, Lookup, LookupOptions, Matcher, SearchPath, Trace, WalkOptions,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The operations a search path needs to search a tree of files and directories. The tree may be
+/// the local disk, see [`OsFileSystem`](struct.OsFileSystem.html), or anything that can be
+/// modelled using these operations, such as an archive, an in-memory tree, or a snapshot of a
+/// remote file system. Paths are those of the search path joined with the names being looked up,
+/// and are not otherwise interpreted.
+///
+/// Errors that mean an entry does not exist, such as `NotFound`, are treated by the lookups as
+/// if the entry is missing rather than reported.
+///
+pub trait FileSystem {
+    ///
+    /// Return the metadata of the entry at `path`, following symbolic links. If `path` is itself
+    /// a symbolic link the metadata is that of its target, marked using
+    /// [`Metadata::with_symlink`](struct.Metadata.html#method.with_symlink).
+    ///
+    fn metadata(&self, path: &Path) -> io::Result<Metadata>;
+
+    ///
+    /// Return the metadata of the entry at `path` without following a symbolic link. By default
+    /// this is the same as [`metadata`](trait.FileSystem.html#tymethod.metadata), which is
+    /// correct for trees without symbolic links.
+    ///
+    fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata> {
+        self.metadata(path)
+    }
+
+    ///
+    /// Return the paths of the entries of the directory at `path`, in any order.
+    ///
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+
+    ///
+    /// Open the file at `path` for reading.
+    ///
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read>>;
+
+    ///
+    /// Return the canonical form of `path`, with all symbolic links resolved. This is used by the
+    /// walk of a recursive entry to detect directories that have already been searched, for
+    /// example via a symbolic link.
+    ///
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+
+    ///
+    /// Create the file at `path` for writing, replacing any existing file. This is only used to
+    /// save an index, see [`Index::save_in`](struct.Index.html#method.save_in), and by default
+    /// returns an `Unsupported` error, which is correct for trees that are only read.
+    ///
+    fn create(&self, path: &Path) -> io::Result<Box<dyn Write>> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("cannot create {:?} in this file system", path),
+        ))
+    }
+}
+
+///
+/// The file system of the local machine, accessed using `std::fs`. This is the file system used
+/// by all the methods of `SearchPath` without the `_in` suffix, and so lookups using it give the
+/// same results as those methods.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OsFileSystem;
+
+///
+/// The type of an entry in a [`FileSystem`](trait.FileSystem.html).
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FileType {
+    /// A regular file.
+    File,
+    /// A directory.
+    Directory,
+    /// A symbolic link, only returned by
+    /// [`FileSystem::symlink_metadata`](trait.FileSystem.html#method.symlink_metadata).
+    Symlink,
+    /// A named pipe.
+    Fifo,
+    /// A Unix domain socket.
+    Socket,
+    /// A block device.
+    BlockDevice,
+    /// A character device.
+    CharDevice,
+    /// Any other type of entry.
+    Other,
+}
+
+///
+/// The metadata of an entry in a [`FileSystem`](trait.FileSystem.html), this is also the
+/// metadata passed to a [`Matcher`](matcher/trait.Matcher.html).
+///
+/// Whether an entry is executable may be set explicitly, otherwise it is determined from its Unix
+/// permission bits and owner, if known, in the same way as for the local disk.
+///
+/// ```rust
+/// use search_path::{FileType, Metadata};
+///
+/// let metadata = Metadata::new(FileType::File).with_size(1024).with_executable(true);
+/// assert!(metadata.is_executable());
+/// ```
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Metadata {
+    file_type: FileType,
+    size: u64,
+    modified: Option<SystemTime>,
+    executable: Option<bool>,
+    readonly: bool,
+    symlink: bool,
+    mode: Option<u32>,
+    owner: Option<(u32, u32)>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl FileSystem for OsFileSystem {
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        // Only a symbolic link needs a second call to read the metadata of its target.
+        let metadata = fs::symlink_metadata(path)?;
+        if metadata.file_type().is_symlink() {
+            fs::metadata(path).map(|metadata| Metadata::from(&metadata).with_symlink(true))
+        } else {
+            Ok(Metadata::from(&metadata))
+        }
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata> {
+        fs::symlink_metadata(path).map(|metadata| Metadata::from(&metadata))
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(fs::read_dir(path)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .collect())
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read>> {
+        Ok(Box::new(fs::File::open(path)?))
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::canonicalize(path)
+    }
+
+    fn create(&self, path: &Path) -> io::Result<Box<dyn Write>> {
+        Ok(Box::new(fs::File::create(path)?))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl From<&fs::Metadata> for Metadata {
+    #[cfg(unix)]
+    fn from(metadata: &fs::Metadata) -> Self {
+        use std::os::unix::fs::MetadataExt;
+        Self::from_std(metadata)
+            .with_mode(metadata.mode())
+            .with_owner(metadata.uid(), metadata.gid())
+    }
+
+    #[cfg(not(unix))]
+    fn from(metadata: &fs::Metadata) -> Self {
+        Self::from_std(metadata).with_executable(metadata.is_file())
+    }
+}
+
+impl Metadata {
+    ///
+    /// Construct new metadata for an entry of `file_type`, empty, writable, and not executable.
+    ///
+    pub fn new(file_type: FileType) -> Self {
+        Self {
+            file_type,
+            size: 0,
+            modified: None,
+            executable: None,
+            readonly: false,
+            symlink: false,
+            mode: None,
+            owner: None,
+        }
+    }
+
+    ///
+    /// Return a copy of this metadata with the size, in bytes, set to `size`.
+    ///
+    pub fn with_size(self, size: u64) -> Self {
+        Self { size, ..self }
+    }
+
+    ///
+    /// Return a copy of this metadata with the last modification time set to `modified`.
+    ///
+    pub fn with_modified(self, modified: SystemTime) -> Self {
+        Self {
+            modified: Some(modified),
+            ..self
+        }
+    }
+
+    ///
+    /// Return a copy of this metadata with the entry executable by the current user if
+    /// `executable`, rather than as determined by its permission bits and owner.
+    ///
+    pub fn with_executable(self, executable: bool) -> Self {
+        Self {
+            executable: Some(executable),
+            ..self
+        }
+    }
+
+    ///
+    /// Return a copy of this metadata with the entry read-only if `readonly`.
+    ///
+    pub fn with_readonly(self, readonly: bool) -> Self {
+        Self { readonly, ..self }
+    }
+
+    ///
+    /// Return a copy of this metadata marked as that of the target of a symbolic link if
+    /// `symlink`.
+    ///
+    pub fn with_symlink(self, symlink: bool) -> Self {
+        Self { symlink, ..self }
+    }
+
+    ///
+    /// Return a copy of this metadata with the Unix permission bits set to `mode`.
+    ///
+    pub fn with_mode(self, mode: u32) -> Self {
+        Self {
+            mode: Some(mode),
+            ..self
+        }
+    }
+
+    ///
+    /// Return a copy of this metadata with the entry owned by the user `uid` and group `gid`.
+    ///
+    pub fn with_owner(self, uid: u32, gid: u32) -> Self {
+        Self {
+            owner: Some((uid, gid)),
+            ..self
+        }
+    }
+
+    ///
+    /// Return the type of the entry.
+    ///
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    ///
+    /// Return `true` if the entry is a regular file, else `false`.
+    ///
+    pub fn is_file(&self) -> bool {
+        self.file_type == FileType::File
+    }
+
+    ///
+    /// Return `true` if the entry is a directory, else `false`.
+    ///
+    pub fn is_dir(&self) -> bool {
+        self.file_type == FileType::Directory
+    }
+
+    ///
+    /// Return the size of the entry, in bytes.
+    ///
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    ///
+    /// Return the last modification time of the entry, if known.
+    ///
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+
+    ///
+    /// Return `true` if the entry is a regular file the current user is permitted to execute,
+    /// else `false`.
+    ///
+    pub fn is_executable(&self) -> bool {
+        self.is_file()
+            && match (self.executable, self.mode, self.owner) {
+                (Some(executable), _, _) => executable,
+                (None, Some(mode), Some((uid, gid))) => crate::is_executable_mode(mode, uid, gid),
+                _ => false,
+            }
+    }
+
+    ///
+    /// Return `true` if the entry may not be written to, else `false`.
+    ///
+    pub fn is_readonly(&self) -> bool {
+        self.readonly
+    }
+
+    ///
+    /// Return `true` if this is the metadata of the target of a symbolic link, else `false`.
+    ///
+    pub fn is_symlink(&self) -> bool {
+        self.symlink
+    }
+
+    ///
+    /// Return the Unix permission bits of the entry, if known.
+    ///
+    pub fn mode(&self) -> Option<u32> {
+        self.mode
+    }
+
+    ///
+    /// Return the user identifier of the owner of the entry, if known.
+    ///
+    pub fn uid(&self) -> Option<u32> {
+        self.owner.map(|(uid, _)| uid)
+    }
+
+    ///
+    /// Return the group identifier of the owner of the entry, if known.
+    ///
+    pub fn gid(&self) -> Option<u32> {
+        self.owner.map(|(_, gid)| gid)
+    }
+
+    fn from_std(metadata: &fs::Metadata) -> Self {
+        Self {
+            modified: metadata.modified().ok(),
+            ..Self::new(FileType::from(metadata.file_type()))
+                .with_size(metadata.len())
+                .with_readonly(metadata.permissions().readonly())
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl From<fs::FileType> for FileType {
+    fn from(file_type: fs::FileType) -> Self {
+        if file_type.is_file() {
+            FileType::File
+        } else if file_type.is_dir() {
+            FileType::Directory
+        } else if file_type.is_symlink() {
+            FileType::Symlink
+        } else {
+            special_file_type(file_type)
+        }
+    }
+}
+
+#[cfg(unix)]
+fn special_file_type(file_type: fs::FileType) -> FileType {
+    use std::os::unix::fs::FileTypeExt;
+    if file_type.is_fifo() {
+        FileType::Fifo
+    } else if file_type.is_socket() {
+        FileType::Socket
+    } else if file_type.is_block_device() {
+        FileType::BlockDevice
+    } else if file_type.is_char_device() {
+        FileType::CharDevice
+    } else {
+        FileType::Other
+    }
+}
+
+#[cfg(not(unix))]
+fn special_file_type(_: fs::FileType) -> FileType {
+    FileType::Other
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl SearchPath {
+    ///
+    /// Return the first entity, either file or directory, found in the search path within
+    /// `file_system`, or `None`. This is the same as [`find`](struct.SearchPath.html#method.find)
+    /// except that entries are read from `file_system` rather than the local disk; recursive
+    /// entries are walked, and any attached index and budget used, in the same way. Any attached
+    /// negative cache is neither used nor updated, as it records misses on the local disk.
+    ///
+    /// ```rust
+    /// use search_path::{OsFileSystem, SearchPath};
+    /// use std::path::Path;
+    ///
+    /// let search_path = SearchPath::new_or_default("PATH");
+    /// assert_eq!(
+    ///     search_path.find_in(&OsFileSystem, Path::new("sh")),
+    ///     search_path.find(Path::new("sh"))
+    /// );
+    /// ```
+    ///
+    pub fn find_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+    ) -> Option<PathBuf> {
+        self.find_somethings_in(file_system, file_name, EntryKind::Any, true)
+            .pop()
+    }
+
+    ///
+    /// Return all the entities, either file or directory, found in the search path within
+    /// `file_system`, see [`find_in`](struct.SearchPath.html#method.find_in).
+    ///
+    pub fn find_all_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+    ) -> Vec<PathBuf> {
+        self.find_somethings_in(file_system, file_name, EntryKind::Any, false)
+    }
+
+    ///
+    /// Return all the _files_ found in the search path within `file_system`, see
+    /// [`find_in`](struct.SearchPath.html#method.find_in).
+    ///
+    pub fn find_all_files_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+    ) -> Vec<PathBuf> {
+        self.find_somethings_in(file_system, file_name, EntryKind::File, false)
+    }
+
+    ///
+    /// Return all the _directories_ found in the search path within `file_system`, see
+    /// [`find_in`](struct.SearchPath.html#method.find_in).
+    ///
+    pub fn find_all_directories_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+    ) -> Vec<PathBuf> {
+        self.find_somethings_in(file_system, file_name, EntryKind::Directory, false)
+    }
+
+    ///
+    /// Return all the entities of the given `kind` found in the search path within
+    /// `file_system`, see [`find_in`](struct.SearchPath.html#method.find_in).
+    ///
+    pub fn find_all_of_kind_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+        kind: EntryKind,
+    ) -> Vec<PathBuf> {
+        self.find_somethings_in(file_system, file_name, kind, false)
+    }
+
+    ///
+    /// Return an iterator over all the entities of the given `kind` found in the search path
+    /// within `file_system`. As for [`matches`](struct.SearchPath.html#method.matches) each
+    /// directory is only checked when the iterator reaches it, unless a budget is attached.
+    ///
+    pub fn matches_in<'a, F: FileSystem + ?Sized>(
+        &'a self,
+        file_system: &'a F,
+        file_name: &Path,
+        kind: EntryKind,
+    ) -> impl Iterator<Item = PathBuf> + 'a {
+        let matches: Box<dyn Iterator<Item = PathBuf> + 'a> = match &self.lookup_options {
+            Some(_) => Box::new(
+                self.find_somethings_in(file_system, file_name, kind, false)
+                    .into_iter(),
+            ),
+            None => Box::new(self.checked_candidates(file_system, file_name, kind)),
+        };
+        matches
+    }
+
+    ///
+    /// Return the first _file_ found in the search path within `file_system`, or `None`, see
+    /// [`find_in`](struct.SearchPath.html#method.find_in).
+    ///
+    pub fn find_file_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+    ) -> Option<PathBuf> {
+        self.find_somethings_in(file_system, file_name, EntryKind::File, true)
+            .pop()
+    }
+
+    ///
+    /// Return the first _directory_ found in the search path within `file_system`, or `None`,
+    /// see [`find_in`](struct.SearchPath.html#method.find_in).
+    ///
+    pub fn find_directory_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+    ) -> Option<PathBuf> {
+        self.find_somethings_in(file_system, file_name, EntryKind::Directory, true)
+            .pop()
+    }
+
+    ///
+    /// Return the first _executable file_ found in the search path within `file_system`, or
+    /// `None`, as reported by [`Metadata::is_executable`](struct.Metadata.html#method.is_executable);
+    /// see [`find_in`](struct.SearchPath.html#method.find_in).
+    ///
+    pub fn find_executable_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+    ) -> Option<PathBuf> {
+        self.find_somethings_in(file_system, file_name, EntryKind::Executable, true)
+            .pop()
+    }
+
+    ///
+    /// Return all the _executable files_ found in the search path within `file_system`, see
+    /// [`find_executable_in`](struct.SearchPath.html#method.find_executable_in).
+    ///
+    pub fn find_all_executables_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+    ) -> Vec<PathBuf> {
+        self.find_somethings_in(file_system, file_name, EntryKind::Executable, false)
+    }
+
+    ///
+    /// Return the first entity whose name matches the regular expression `regex` in the search
+    /// path within `file_system`, or `None`, as
+    /// [`find_regex`](struct.SearchPath.html#method.find_regex).
+    ///
+    /// This method is only available with the `regex` feature.
+    ///
+    #[cfg(feature = "regex")]
+    pub fn find_regex_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        regex: &regex::Regex,
+    ) -> Option<RegexMatch> {
+        self.directories(file_system).find_map(|path| {
+            RegexMatch::find_in(file_system, regex, &path)
+                .into_iter()
+                .next()
+        })
+    }
+
+    ///
+    /// Return all the entities whose name matches the regular expression `regex` in the search
+    /// path within `file_system`, as
+    /// [`find_all_regex`](struct.SearchPath.html#method.find_all_regex).
+    ///
+    /// This method is only available with the `regex` feature.
+    ///
+    #[cfg(feature = "regex")]
+    pub fn find_all_regex_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        regex: &regex::Regex,
+    ) -> Vec<RegexMatch> {
+        self.directories(file_system)
+            .flat_map(|path| RegexMatch::find_in(file_system, regex, &path))
+            .collect()
+    }
+
+    ///
+    /// Return the first entity found in the search path within `file_system`, or `None`, if
+    /// `file_name` is a path, as
+    /// [`find_if_name_only`](struct.SearchPath.html#method.find_if_name_only).
+    ///
+    pub fn find_if_name_only_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+    ) -> Option<PathBuf> {
+        if file_name.parent().is_some() {
+            self.find_in(file_system, file_name)
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Return the first entity found in the search path, or in any sub-directory of an entry in
+    /// the search path to a depth of `max_depth`, within `file_system`, or `None`, as
+    /// [`find_recursive`](struct.SearchPath.html#method.find_recursive).
+    ///
+    pub fn find_recursive_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+        max_depth: usize,
+    ) -> Option<PathBuf> {
+        let options = WalkOptions::default().with_max_depth(max_depth);
+        self.find_recursive_with_in(file_system, file_name, &options)
+    }
+
+    ///
+    /// Return the first entity found in the search path, or in any sub-directory of an entry in
+    /// the search path as determined by `options`, within `file_system`, or `None`, as
+    /// [`find_recursive_with`](struct.SearchPath.html#method.find_recursive_with).
+    ///
+    pub fn find_recursive_with_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+        options: &WalkOptions,
+    ) -> Option<PathBuf> {
+        let request = self.request(file_name, EntryKind::Any, true);
+        self.lookup_in(file_system, &request.with_walk(options))
+            .into_iter()
+            .flatten()
+            .next()
+    }
+
+    ///
+    /// Return all the entities found in the search path, or in any sub-directory of an entry in
+    /// the search path to a depth of `max_depth`, within `file_system`, as
+    /// [`find_all_recursive`](struct.SearchPath.html#method.find_all_recursive).
+    ///
+    pub fn find_all_recursive_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+        max_depth: usize,
+    ) -> Vec<PathBuf> {
+        let options = WalkOptions::default().with_max_depth(max_depth);
+        self.find_all_recursive_with_in(file_system, file_name, &options)
+    }
+
+    ///
+    /// Return all the entities found in the search path, or in any sub-directory of an entry in
+    /// the search path as determined by `options`, within `file_system`, as
+    /// [`find_all_recursive_with`](struct.SearchPath.html#method.find_all_recursive_with).
+    ///
+    pub fn find_all_recursive_with_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+        options: &WalkOptions,
+    ) -> Vec<PathBuf> {
+        let request = self.request(file_name, EntryKind::Any, false);
+        self.lookup_in(file_system, &request.with_walk(options))
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    ///
+    /// Return the first entity that matches the glob `pattern` in the search path within
+    /// `file_system`, or `None`, as [`find_glob`](struct.SearchPath.html#method.find_glob).
+    ///
+    pub fn find_glob_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        pattern: &str,
+    ) -> Result<Option<PathBuf>, Error> {
+        let glob = Glob::new(pattern)?;
+        Ok(self
+            .directories(file_system)
+            .find_map(|path| glob.find_in(file_system, &path).into_iter().next()))
+    }
+
+    ///
+    /// Return all the entities that match the glob `pattern` in the search path within
+    /// `file_system`, as [`find_all_glob`](struct.SearchPath.html#method.find_all_glob).
+    ///
+    pub fn find_all_glob_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        pattern: &str,
+    ) -> Result<Vec<PathBuf>, Error> {
+        let glob = Glob::new(pattern)?;
+        Ok(self
+            .directories(file_system)
+            .flat_map(|path| glob.find_in(file_system, &path))
+            .collect())
+    }
+
+    ///
+    /// Return the first entity found in the search path within `file_system`, or `None`,
+    /// reporting unexpected I/O errors as described for
+    /// [`try_find`](struct.SearchPath.html#method.try_find). Any deadline in `options` is only
+    /// checked before each probe, see [`LookupOptions`](struct.LookupOptions.html).
+    ///
+    pub fn try_find_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Option<PathBuf>>, Error> {
+        self.try_find_something_in(file_system, file_name, EntryKind::Any, options)
+    }
+
+    ///
+    /// Return the first _file_ found in the search path within `file_system`, or `None`, see
+    /// [`try_find_in`](struct.SearchPath.html#method.try_find_in).
+    ///
+    pub fn try_find_file_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Option<PathBuf>>, Error> {
+        self.try_find_something_in(file_system, file_name, EntryKind::File, options)
+    }
+
+    ///
+    /// Return the first _directory_ found in the search path within `file_system`, or `None`,
+    /// see [`try_find_in`](struct.SearchPath.html#method.try_find_in).
+    ///
+    pub fn try_find_directory_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Option<PathBuf>>, Error> {
+        self.try_find_something_in(file_system, file_name, EntryKind::Directory, options)
+    }
+
+    ///
+    /// Return the first _executable file_ found in the search path within `file_system`, or
+    /// `None`, see [`try_find_in`](struct.SearchPath.html#method.try_find_in).
+    ///
+    pub fn try_find_executable_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Option<PathBuf>>, Error> {
+        self.try_find_something_in(file_system, file_name, EntryKind::Executable, options)
+    }
+
+    ///
+    /// Return all the entities found in the search path within `file_system`, see
+    /// [`try_find_in`](struct.SearchPath.html#method.try_find_in).
+    ///
+    pub fn try_find_all_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Vec<PathBuf>>, Error> {
+        let request = self.request(file_name, EntryKind::Any, false);
+        Ok(self
+            .try_lookup_in(file_system, &request, options)?
+            .map(|found| found.into_iter().flatten().collect()))
+    }
+
+    fn try_find_something_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+        kind: EntryKind,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Option<PathBuf>>, Error> {
+        let request = self.request(file_name, kind, true);
+        Ok(self
+            .try_lookup_in(file_system, &request, options)?
+            .map(|found| found.into_iter().flatten().next()))
+    }
+
+    ///
+    /// Perform a lookup for `file_name` of the given `kind` within `file_system`, returning a
+    /// `Trace` of every candidate considered, as [`explain`](struct.SearchPath.html#method.explain).
+    ///
+    pub fn explain_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+        kind: EntryKind,
+    ) -> Trace {
+        Trace::new(
+            file_system,
+            file_name,
+            kind,
+            self.candidates(file_system, file_name),
+        )
+    }
+
+    ///
+    /// Return the first entity in any directory in the search path within `file_system` that is
+    /// selected by `matcher`, or `None`, as
+    /// [`find_matching`](struct.SearchPath.html#method.find_matching).
+    ///
+    pub fn find_matching_in<F: FileSystem + ?Sized, M: Matcher>(
+        &self,
+        file_system: &F,
+        matcher: &M,
+    ) -> Option<PathBuf> {
+        self.find_all_matching_in(file_system, matcher).next()
+    }
+
+    ///
+    /// Return an iterator over all the entities in all the directories in the search path within
+    /// `file_system` that are selected by `matcher`, as
+    /// [`find_all_matching`](struct.SearchPath.html#method.find_all_matching).
+    ///
+    pub fn find_all_matching_in<'a, F: FileSystem + ?Sized, M: Matcher>(
+        &'a self,
+        file_system: &'a F,
+        matcher: &'a M,
+    ) -> impl Iterator<Item = PathBuf> + 'a {
+        self.directories(file_system)
+            .flat_map(move |path| crate::sorted_entries(file_system, &path))
+            .filter_map(move |(_, path)| {
+                if crate::is_match_path(file_system, matcher, &path) {
+                    Some(path)
+                } else {
+                    None
+                }
+            })
+    }
+
+    ///
+    /// Return the first entity found in the search path within `file_system` for each of
+    /// `names`, as [`find_many`](struct.SearchPath.html#method.find_many).
+    ///
+    pub fn find_many_in<F, I, P>(&self, file_system: &F, names: I) -> HashMap<PathBuf, PathBuf>
+    where
+        F: FileSystem + ?Sized,
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.find_many_of_kind_in(file_system, names, EntryKind::Any)
+    }
+
+    ///
+    /// Return the first entity of the given `kind` found in the search path within
+    /// `file_system` for each of `names`, as
+    /// [`find_many_of_kind`](struct.SearchPath.html#method.find_many_of_kind).
+    ///
+    pub fn find_many_of_kind_in<F, I, P>(
+        &self,
+        file_system: &F,
+        names: I,
+        kind: EntryKind,
+    ) -> HashMap<PathBuf, PathBuf>
+    where
+        F: FileSystem + ?Sized,
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.scan_many(names, kind, true, None, |request| {
+            self.lookup_in(file_system, request)
+        })
+        .into_iter()
+        .map(|(name, mut paths)| (name, paths.swap_remove(0)))
+        .collect()
+    }
+
+    ///
+    /// Return all the entities found in the search path within `file_system` for each of
+    /// `names`, as [`find_all_many`](struct.SearchPath.html#method.find_all_many).
+    ///
+    pub fn find_all_many_in<F, I, P>(
+        &self,
+        file_system: &F,
+        names: I,
+    ) -> HashMap<PathBuf, Vec<PathBuf>>
+    where
+        F: FileSystem + ?Sized,
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.find_all_many_of_kind_in(file_system, names, EntryKind::Any)
+    }
+
+    ///
+    /// Return all the entities of the given `kind` found in the search path within
+    /// `file_system` for each of `names`, as
+    /// [`find_all_many_of_kind`](struct.SearchPath.html#method.find_all_many_of_kind).
+    ///
+    pub fn find_all_many_of_kind_in<F, I, P>(
+        &self,
+        file_system: &F,
+        names: I,
+        kind: EntryKind,
+    ) -> HashMap<PathBuf, Vec<PathBuf>>
+    where
+        F: FileSystem + ?Sized,
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.scan_many(names, kind, false, None, |request| {
+            self.lookup_in(file_system, request)
+        })
+    }
+
+    fn find_somethings_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        file_name: &Path,
+        kind: EntryKind,
+        first_only: bool,
+    ) -> Vec<PathBuf> {
+        self.lookup_in(file_system, &self.request(file_name, kind, first_only))
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
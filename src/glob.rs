@@ -0,0 +1,345 @@
+/*!
+Provides a simple glob pattern matcher used to find entries within each directory of a search path,
+see [`SearchPath::find_glob`](../struct.SearchPath.html#method.find_glob).
+
+The supported syntax is:
+
+* `?` matches any single character.
+* `*` matches any sequence of characters, within a single path component.
+* `[...]` matches any one of the enclosed characters, or ranges such as `a-z`; if the first
+  character is `!` or `^` the set is negated. An unclosed `[` is matched literally.
+* `{a,b}` matches any of the comma separated alternatives, which may themselves contain patterns.
+* `**`, as an entire path component, matches zero or more directories.
+
+As is the case for shells, a leading `.` in a file name must be matched explicitly.
+*/
+
+use crate::{Error, FileSystem};
+use std::path::{self, Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A compiled glob pattern, this may have more than one alternative following brace expansion.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Glob {
+    alternatives: Vec<Vec<Component>>,
+}
+
+///
+/// A compiled pattern for a single path component, that is a file or directory name.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct NamePattern {
+    tokens: Vec<Token>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq)]
+enum Component {
+    Literal(String),
+    Pattern(NamePattern),
+    Recursive,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Char(char),
+    AnyChar,
+    AnyString,
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+const RECURSIVE_COMPONENT: &str = "**";
+
+const HIDDEN_PREFIX_CHAR: char = '.';
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Glob {
+    ///
+    /// Compile `pattern`, returning an error if any of its alternatives is an absolute path, as
+    /// patterns are matched below each directory in a search path.
+    ///
+    pub(crate) fn new(pattern: &str) -> Result<Self, Error> {
+        let alternatives = expand_braces(pattern);
+        if alternatives.iter().any(|alternative| {
+            matches!(
+                Path::new(alternative).components().next(),
+                Some(path::Component::RootDir | path::Component::Prefix(_))
+            )
+        }) {
+            return Err(Error::invalid_pattern(
+                pattern,
+                "patterns are relative to each directory and may not be absolute",
+            ));
+        }
+        Ok(Self {
+            alternatives: alternatives
+                .iter()
+                .map(|alternative| {
+                    let mut components: Vec<Component> = alternative
+                        .split('/')
+                        .filter(|c| !c.is_empty() && *c != ".")
+                        .map(Component::new)
+                        .collect();
+                    // A trailing `**` matches everything below the directory, but not the
+                    // directory itself.
+                    if components.last() == Some(&Component::Recursive) {
+                        components.push(Component::Pattern(NamePattern::new("*")));
+                    }
+                    components
+                })
+                .filter(|components| !components.is_empty())
+                .collect(),
+        })
+    }
+
+    ///
+    /// Return all the paths below `dir` within `file_system` that match this pattern, sorted and
+    /// without duplicates.
+    ///
+    pub(crate) fn find_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        dir: &Path,
+    ) -> Vec<PathBuf> {
+        let mut results = Vec::new();
+        for components in &self.alternatives {
+            walk(file_system, dir, components, &mut results);
+        }
+        results.sort();
+        results.dedup();
+        results
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Component {
+    fn new(s: &str) -> Self {
+        if s == RECURSIVE_COMPONENT {
+            Component::Recursive
+        } else {
+            let pattern = NamePattern::new(s);
+            if pattern.tokens.iter().all(|t| matches!(t, Token::Char(_))) {
+                Component::Literal(s.to_string())
+            } else {
+                Component::Pattern(pattern)
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl NamePattern {
+    pub(crate) fn new(s: &str) -> Self {
+        let chars: Vec<char> = s.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            match chars[i] {
+                '?' => tokens.push(Token::AnyChar),
+                '*' => {
+                    if tokens.last() != Some(&Token::AnyString) {
+                        tokens.push(Token::AnyString)
+                    }
+                }
+                '[' => match parse_class(&chars[i + 1..]) {
+                    Some((token, length)) => {
+                        tokens.push(token);
+                        i += length;
+                    }
+                    None => tokens.push(Token::Char('[')),
+                },
+                c => tokens.push(Token::Char(c)),
+            }
+            i += 1;
+        }
+        Self { tokens }
+    }
+
+    pub(crate) fn is_match(&self, name: &str) -> bool {
+        let name: Vec<char> = name.chars().collect();
+        if name.first() == Some(&HIDDEN_PREFIX_CHAR)
+            && self.tokens.first() != Some(&Token::Char(HIDDEN_PREFIX_CHAR))
+        {
+            return false;
+        }
+        let (mut t, mut n) = (0, 0);
+        let mut backtrack: Option<(usize, usize)> = None;
+        while n < name.len() {
+            match self.tokens.get(t) {
+                Some(Token::AnyString) => {
+                    backtrack = Some((t, n));
+                    t += 1;
+                }
+                Some(token) if token.is_match(name[n]) => {
+                    t += 1;
+                    n += 1;
+                }
+                _ => match backtrack {
+                    Some((star_t, star_n)) => {
+                        backtrack = Some((star_t, star_n + 1));
+                        t = star_t + 1;
+                        n = star_n + 1;
+                    }
+                    None => return false,
+                },
+            }
+        }
+        self.tokens[t..].iter().all(|t| *t == Token::AnyString)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Token {
+    fn is_match(&self, c: char) -> bool {
+        match self {
+            Token::Char(expected) => *expected == c,
+            Token::AnyChar => true,
+            Token::AnyString => false,
+            Token::Class { negated, ranges } => {
+                ranges.iter().any(|(start, end)| *start <= c && c <= *end) != *negated
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Parse a character class, `chars` starts immediately after the opening `[`. Returns the token
+/// and the number of characters consumed, including the closing `]`, or `None` if the class is
+/// not closed.
+///
+fn parse_class(chars: &[char]) -> Option<(Token, usize)> {
+    let mut i = 0;
+    let negated = matches!(chars.first(), Some('!') | Some('^'));
+    if negated {
+        i += 1;
+    }
+    let mut ranges = Vec::new();
+    let start = i;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == ']' && i > start {
+            return Some((Token::Class { negated, ranges }, i + 1));
+        } else if i + 2 < chars.len() && chars[i + 1] == '-' && chars[i + 2] != ']' {
+            ranges.push((c, chars[i + 2]));
+            i += 3;
+        } else {
+            ranges.push((c, c));
+            i += 1;
+        }
+    }
+    None
+}
+
+///
+/// Expand the first brace group in `pattern` that is closed and contains a top-level comma,
+/// recursively, returning all the alternatives. Braces that do not form such a group are kept
+/// literally.
+///
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    for open in (0..chars.len()).filter(|i| chars[*i] == '{') {
+        let mut depth = 0;
+        let mut commas = Vec::new();
+        for close in open + 1..chars.len() {
+            match chars[close] {
+                '{' => depth += 1,
+                '}' if depth > 0 => depth -= 1,
+                ',' if depth == 0 => commas.push(close),
+                '}' => {
+                    if commas.is_empty() {
+                        break;
+                    }
+                    let prefix: String = chars[..open].iter().collect();
+                    let suffix: String = chars[close + 1..].iter().collect();
+                    let mut bounds = vec![open];
+                    bounds.extend(commas);
+                    bounds.push(close);
+                    return bounds
+                        .windows(2)
+                        .flat_map(|w| {
+                            let alternative: String = chars[w[0] + 1..w[1]].iter().collect();
+                            expand_braces(&format!("{}{}{}", prefix, alternative, suffix))
+                        })
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+    }
+    vec![pattern.to_string()]
+}
+
+fn walk<F: FileSystem + ?Sized>(
+    file_system: &F,
+    dir: &Path,
+    components: &[Component],
+    results: &mut Vec<PathBuf>,
+) {
+    let (component, rest) = match components.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+    match component {
+        Component::Literal(name) => {
+            let path = dir.join(name);
+            if rest.is_empty() {
+                // As for the _find_ methods, a broken symbolic link does not exist.
+                if file_system.metadata(&path).is_ok() {
+                    results.push(path);
+                }
+            } else if is_dir(file_system, &path, true) {
+                walk(file_system, &path, rest, results);
+            }
+        }
+        Component::Pattern(pattern) => {
+            for (name, path) in crate::sorted_entries(file_system, dir) {
+                if pattern.is_match(&name) {
+                    if rest.is_empty() {
+                        if file_system.metadata(&path).is_ok() {
+                            results.push(path);
+                        }
+                    } else if is_dir(file_system, &path, true) {
+                        walk(file_system, &path, rest, results);
+                    }
+                }
+            }
+        }
+        Component::Recursive => {
+            walk(file_system, dir, rest, results);
+            for (name, path) in crate::sorted_entries(file_system, dir) {
+                // Symbolic links are not followed, which also guards against cycles.
+                if is_dir(file_system, &path, false) && !name.starts_with(HIDDEN_PREFIX_CHAR) {
+                    walk(file_system, &path, components, results);
+                }
+            }
+        }
+    }
+}
+
+fn is_dir<F: FileSystem + ?Sized>(file_system: &F, path: &Path, follow_symlinks: bool) -> bool {
+    matches!(
+        file_system.metadata(path),
+        Ok(metadata) if metadata.is_dir() && (follow_symlinks || !metadata.is_symlink())
+    )
+}
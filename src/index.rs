@@ -0,0 +1,544 @@
+/*!
+Provides a persistent index, in the spirit of the kpathsea `ls-R` database, mapping file names to
+the directories that contain them, see [`SearchPath::set_index`](../struct.SearchPath.html#method.set_index).
+*/
+
+use crate::{Entry, Error, FileSystem, OsFileSystem, SearchPath, Walk, WalkOptions};
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::ffi::{OsStr, OsString};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An index mapping file names to the directories, below a set of root entries, that contain them.
+/// An index is constructed using an [`IndexBuilder`](struct.IndexBuilder.html), may be saved to and
+/// loaded from disk, and is consulted by the _find_ methods of any `SearchPath` it is attached to.
+///
+/// An index is a snapshot, it is not updated as the file system changes. The
+/// [`is_stale`](struct.Index.html#method.is_stale) method compares the recorded modification time
+/// of each directory with its current value, and [`rebuild`](struct.Index.html#method.rebuild)
+/// constructs a new index from the same root entries.
+///
+/// # File Format
+///
+/// An index is written as a single binary file, all integers are little-endian and all byte strings
+/// are written as a `u32` length followed by the bytes themselves. On Unix platforms paths and names
+/// are written as their raw bytes, elsewhere they must be valid Unicode and are written as UTF-8.
+///
+/// * The 8 byte magic value `SPINDEX\0`, followed by the `u32` format version, currently `1`.
+/// * A `u32` count of the root entries, each is the path of the entry followed by a `u8` which is
+///   `1` if the entry is recursive, else `0`.
+/// * A `u32` count of the directories, each is the path of the directory, its modification time,
+///   and a `u32` count of the names it contains followed by each name. The modification time is a
+///   `u8` which is `0` if the time is not recorded, else `1` followed by the `u64` seconds and `u32`
+///   nanoseconds since the Unix epoch.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use search_path::{Index, ParseOptions, SearchPath};
+/// use std::path::Path;
+///
+/// let options = ParseOptions::default().with_kpathsea(true);
+/// let mut search_path = SearchPath::new_with_options("TEXINPUTS", &options).unwrap();
+/// let index_file = Path::new("ls-R.idx");
+/// let index = match Index::load(index_file) {
+///     Ok(index) if !index.is_stale() => index,
+///     _ => {
+///         let index = Index::builder().with_search_path(&search_path).build();
+///         index.save(index_file).unwrap();
+///         index
+///     }
+/// };
+/// search_path.set_index(index);
+/// let article = search_path.find_file(Path::new("article.cls"));
+/// ```
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Index {
+    roots: Vec<Entry>,
+    directories: Vec<Directory>,
+    names: HashMap<OsString, Vec<usize>>,
+}
+
+///
+/// A builder for an [`Index`](struct.Index.html). Each root entry added to the builder is indexed;
+/// for a recursive entry all of its sub-directories are also indexed, walked breadth-first in the
+/// same order as the _find_ methods.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexBuilder {
+    roots: Vec<Entry>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Directory {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    names: Vec<OsString>,
+}
+
+const MAGIC: &[u8; 8] = b"SPINDEX\0";
+
+const FORMAT_VERSION: u32 = 1;
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Index {
+    ///
+    /// Return a new, empty, builder.
+    ///
+    pub fn builder() -> IndexBuilder {
+        IndexBuilder::default()
+    }
+
+    ///
+    /// Construct a new index with the same root entries as this one, reading the current contents
+    /// of the file system.
+    ///
+    pub fn rebuild(&self) -> Self {
+        self.rebuild_in(&OsFileSystem)
+    }
+
+    ///
+    /// Construct a new index with the same root entries as this one, reading the current contents
+    /// of `file_system`.
+    ///
+    pub fn rebuild_in<F: FileSystem + ?Sized>(&self, file_system: &F) -> Self {
+        IndexBuilder {
+            roots: self.roots.clone(),
+        }
+        .build_in(file_system)
+    }
+
+    ///
+    /// Load an index previously written by [`save`](struct.Index.html#method.save) from the file
+    /// at `path`.
+    ///
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        Self::load_in(&OsFileSystem, path)
+    }
+
+    ///
+    /// Load an index previously written by [`save`](struct.Index.html#method.save) from the file
+    /// at `path` within `file_system`.
+    ///
+    pub fn load_in<F: FileSystem + ?Sized>(file_system: &F, path: &Path) -> Result<Self, Error> {
+        file_system
+            .open(path)
+            .and_then(|file| Self::read_from(&mut BufReader::new(file)))
+            .map_err(|e| Error::io(path, e))
+    }
+
+    ///
+    /// Save this index to the file at `path`, replacing any existing file.
+    ///
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        self.save_in(&OsFileSystem, path)
+    }
+
+    ///
+    /// Save this index to the file at `path` within `file_system`, replacing any existing file,
+    /// see [`FileSystem::create`](trait.FileSystem.html#method.create).
+    ///
+    pub fn save_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        path: &Path,
+    ) -> Result<(), Error> {
+        file_system
+            .create(path)
+            .and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                self.write_to(&mut writer)?;
+                writer.flush()
+            })
+            .map_err(|e| Error::io(path, e))
+    }
+
+    ///
+    /// Read an index, in the format described for the [`Index`](struct.Index.html) type, from
+    /// `reader`.
+    ///
+    pub(crate) fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a search path index"));
+        }
+        let version = read_u32(reader)?;
+        if version != FORMAT_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported search path index version {}",
+                version
+            )));
+        }
+        let mut roots = Vec::new();
+        for _ in 0..read_u32(reader)? {
+            let path = PathBuf::from(read_os_string(reader)?);
+            roots.push(Entry::new(path).with_recursive(read_u8(reader)? != 0));
+        }
+        let mut directories = Vec::new();
+        for _ in 0..read_u32(reader)? {
+            let path = PathBuf::from(read_os_string(reader)?);
+            let modified = match read_u8(reader)? {
+                0 => None,
+                _ => {
+                    let secs = read_u64(reader)?;
+                    let nanos = read_u32(reader)?;
+                    if nanos >= 1_000_000_000 {
+                        return Err(invalid_data("invalid modification time"));
+                    }
+                    UNIX_EPOCH.checked_add(Duration::new(secs, nanos))
+                }
+            };
+            let mut names = Vec::new();
+            for _ in 0..read_u32(reader)? {
+                names.push(read_os_string(reader)?);
+            }
+            directories.push(Directory {
+                path,
+                modified,
+                names,
+            });
+        }
+        Ok(Self::new(roots, directories))
+    }
+
+    ///
+    /// Write this index, in the format described for the [`Index`](struct.Index.html) type, to
+    /// `writer`.
+    ///
+    pub(crate) fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        write_u32(writer, FORMAT_VERSION)?;
+        write_len(writer, self.roots.len())?;
+        for root in &self.roots {
+            write_os_str(writer, root.path().as_os_str())?;
+            writer.write_all(&[root.is_recursive() as u8])?;
+        }
+        write_len(writer, self.directories.len())?;
+        for directory in &self.directories {
+            write_os_str(writer, directory.path.as_os_str())?;
+            match directory
+                .modified
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            {
+                Some(since) => {
+                    writer.write_all(&[1])?;
+                    writer.write_all(&since.as_secs().to_le_bytes())?;
+                    write_u32(writer, since.subsec_nanos())?;
+                }
+                None => writer.write_all(&[0])?,
+            }
+            write_len(writer, directory.names.len())?;
+            for name in &directory.names {
+                write_os_str(writer, name)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Return an iterator over the root entries this index was built from.
+    ///
+    pub fn roots(&self) -> impl Iterator<Item = &Entry> {
+        self.roots.iter()
+    }
+
+    ///
+    /// Return an iterator over all the directories in this index, in search order.
+    ///
+    pub fn directories(&self) -> impl Iterator<Item = &PathBuf> {
+        self.directories.iter().map(|directory| &directory.path)
+    }
+
+    ///
+    /// Return an iterator over the directories in this index that contain an entry named `name`,
+    /// in search order.
+    ///
+    pub fn directories_containing(&self, name: &OsStr) -> impl Iterator<Item = &PathBuf> {
+        self.names
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(move |i| &self.directories[*i].path)
+    }
+
+    ///
+    /// Return `true` if the modification time of any directory in this index differs from the
+    /// time recorded when it was indexed, else `false`.
+    ///
+    pub fn is_stale(&self) -> bool {
+        self.is_stale_in(&OsFileSystem)
+    }
+
+    ///
+    /// Return `true` if the modification time of any directory in this index, as read from
+    /// `file_system`, differs from the time recorded when it was indexed, else `false`.
+    ///
+    pub fn is_stale_in<F: FileSystem + ?Sized>(&self, file_system: &F) -> bool {
+        self.directories
+            .iter()
+            .any(|directory| directory.is_stale(file_system))
+    }
+
+    ///
+    /// Return all the directories whose modification time differs from the time recorded when
+    /// they were indexed.
+    ///
+    pub fn stale_directories(&self) -> Vec<&PathBuf> {
+        self.stale_directories_in(&OsFileSystem)
+    }
+
+    ///
+    /// Return all the directories whose modification time, as read from `file_system`, differs
+    /// from the time recorded when they were indexed.
+    ///
+    pub fn stale_directories_in<F: FileSystem + ?Sized>(&self, file_system: &F) -> Vec<&PathBuf> {
+        self.directories
+            .iter()
+            .filter(|directory| directory.is_stale(file_system))
+            .map(|directory| &directory.path)
+            .collect()
+    }
+
+    ///
+    /// Return the directories to search for `entry`, limited to those containing one of `names`
+    /// if present, or `None` if `entry` is not covered by this index. As for kpathsea, `None` is
+    /// also returned if none of `names` is recorded for an entry that is not index-only, so that
+    /// it is searched on disk instead.
+    ///
+    pub(crate) fn directories_for(
+        &self,
+        entry: &Entry,
+        names: Option<&[PathBuf]>,
+    ) -> Option<Vec<PathBuf>> {
+        if !self.roots.iter().any(|root| {
+            root.path() == entry.path() && (root.is_recursive() || !entry.is_recursive())
+        }) {
+            return None;
+        }
+        // Only names that are a single component can be looked up, anything else has to
+        // consider every directory.
+        let keys: Option<Vec<&OsStr>> = names.and_then(|names| {
+            names
+                .iter()
+                .map(|name| crate::single_component(name))
+                .collect()
+        });
+        let indices: Vec<usize> = match keys {
+            Some(keys) => {
+                let mut indices: Vec<usize> = keys
+                    .iter()
+                    .flat_map(|key| self.names.get(*key).into_iter().flatten().copied())
+                    .collect();
+                indices.sort_unstable();
+                indices.dedup();
+                indices
+            }
+            None => (0..self.directories.len()).collect(),
+        };
+        let directories: Vec<PathBuf> = indices
+            .into_iter()
+            .map(|i| &self.directories[i].path)
+            .filter(|path| {
+                if entry.is_recursive() {
+                    path.starts_with(entry.path())
+                } else {
+                    *path == entry.path()
+                }
+            })
+            .cloned()
+            .collect();
+        if directories.is_empty() && names.is_some() && !entry.is_index_only() {
+            None
+        } else {
+            Some(directories)
+        }
+    }
+
+    fn new(roots: Vec<Entry>, directories: Vec<Directory>) -> Self {
+        let mut names: HashMap<OsString, Vec<usize>> = Default::default();
+        for (i, directory) in directories.iter().enumerate() {
+            for name in &directory.names {
+                names.entry(name.clone()).or_default().push(i);
+            }
+        }
+        Self {
+            roots,
+            directories,
+            names,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl IndexBuilder {
+    ///
+    /// Return a copy of this builder with each entry in `search_path` added as a root entry,
+    /// including index-only entries.
+    ///
+    pub fn with_search_path(self, search_path: &SearchPath) -> Self {
+        search_path
+            .entries()
+            .cloned()
+            .fold(self, |builder, entry| builder.with_entry(entry))
+    }
+
+    ///
+    /// Return a copy of this builder with `entry` added as a root entry. If the entry is recursive
+    /// all of its sub-directories are also indexed.
+    ///
+    pub fn with_entry(mut self, entry: Entry) -> Self {
+        self.roots
+            .push(Entry::new(entry.path().clone()).with_recursive(entry.is_recursive()));
+        self
+    }
+
+    ///
+    /// Construct a new index by reading each of the root entries, and for recursive entries their
+    /// sub-directories, from the file system. Directories that cannot be read are indexed as if
+    /// they were empty.
+    ///
+    pub fn build(&self) -> Index {
+        self.build_in(&OsFileSystem)
+    }
+
+    ///
+    /// Construct a new index by reading each of the root entries, and for recursive entries their
+    /// sub-directories, from `file_system`, see [`build`](struct.IndexBuilder.html#method.build).
+    ///
+    pub fn build_in<F: FileSystem + ?Sized>(&self, file_system: &F) -> Index {
+        let mut seen: HashSet<PathBuf> = Default::default();
+        let mut directories = Vec::new();
+        for root in &self.roots {
+            let walk: Box<dyn Iterator<Item = PathBuf>> = if root.is_recursive() {
+                Box::new(Walk::new(file_system, root.path(), &WalkOptions::default()))
+            } else {
+                Box::new(std::iter::empty())
+            };
+            for path in std::iter::once(root.path().clone()).chain(walk) {
+                if seen.insert(path.clone()) {
+                    directories.push(Directory::read(file_system, path));
+                }
+            }
+        }
+        Index::new(self.roots.clone(), directories)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Directory {
+    fn read<F: FileSystem + ?Sized>(file_system: &F, path: PathBuf) -> Self {
+        // The time is recorded before reading so that any concurrent change marks it as stale.
+        let modified = crate::modified_time(file_system, &path);
+        let names = crate::sorted_entries(file_system, &path)
+            .into_iter()
+            .filter_map(|(_, path)| path.file_name().map(OsStr::to_os_string))
+            .collect();
+        Self {
+            path,
+            modified,
+            names,
+        }
+    }
+
+    fn is_stale<F: FileSystem + ?Sized>(&self, file_system: &F) -> bool {
+        crate::modified_time(file_system, &self.path) != self.modified
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut bytes = [0; 1];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes[0])
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_os_string<R: Read>(reader: &mut R) -> io::Result<OsString> {
+    let len = read_u32(reader)?;
+    let mut bytes = Vec::new();
+    // Reading through `take` avoids trusting a corrupt length for the allocation.
+    let _ = reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len as usize {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    os_string_from_bytes(bytes)
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_len<W: Write>(writer: &mut W, len: usize) -> io::Result<()> {
+    match u32::try_from(len) {
+        Ok(len) => write_u32(writer, len),
+        Err(_) => Err(invalid_data("too large for a search path index")),
+    }
+}
+
+fn write_os_str<W: Write>(writer: &mut W, s: &OsStr) -> io::Result<()> {
+    let bytes = os_str_bytes(s)?;
+    write_len(writer, bytes.len())?;
+    writer.write_all(bytes)
+}
+
+#[cfg(unix)]
+fn os_str_bytes(s: &OsStr) -> io::Result<&[u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    Ok(s.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn os_str_bytes(s: &OsStr) -> io::Result<&[u8]> {
+    s.to_str()
+        .map(str::as_bytes)
+        .ok_or_else(|| invalid_data(format!("{:?} is not valid Unicode", s)))
+}
+
+#[cfg(unix)]
+fn os_string_from_bytes(bytes: Vec<u8>) -> io::Result<OsString> {
+    use std::os::unix::ffi::OsStringExt;
+    Ok(OsString::from_vec(bytes))
+}
+
+#[cfg(not(unix))]
+fn os_string_from_bytes(bytes: Vec<u8>) -> io::Result<OsString> {
+    String::from_utf8(bytes)
+        .map(OsString::from)
+        .map_err(invalid_data)
+}
@@ -6,15 +6,31 @@ akin to the mechanism a shell uses to find executables using the `PATH` environm
 can be constructed with a search path from an environment variable, from a string, or from a list
 of either string or `Path`/`PathBuf` values. Typically the _find_ methods return the first
 matching file or directory, but the `find_all` method specifically collects and returns a list of
-all matching paths.
+all matching paths. The `find_executable` method, and the `which` function, only return files that
+the current user is permitted to execute.
 
 # Constructors
 
 The `SearchPath` type also has `From<>` implementations for `PathBuf`, `Vec<PathBuf>`, `Vec<&Path>`,
-`Vec<&str>`, `String`, and `&str`. In the case of vector values, or a single `PathBuf`, each path
-value will be used as-is without trying to split it into components. In the case of individual
-`String` and `&str` values the value will be split using the platform specific path separator
-into individual paths components.
+`Vec<&str>`, `String`, `&str`, `OsString`, and `&OsStr`. In the case of vector values, or a single
+`PathBuf`, each path value will be used as-is without trying to split it into components. In the
+case of individual string values the value will be split using the platform specific path
+separator into individual paths components; `OsString` and `&OsStr` values are split without
+requiring them to be valid Unicode.
+
+The `parse` constructor can also recognize the kpathsea syntax used by TeX, in which a trailing
+`//` marks an [`Entry`](struct.Entry.html) whose sub-directories are also searched, and a leading
+`!!` marks an entry that may only be searched using an index.
+
+# Features
+
+* `parallel`; enables the `find_parallel` and `find_all_parallel` methods, which probe every entry
+  in the search path concurrently.
+* `regex`; enables the `find_regex` and `find_all_regex` methods, which match entry names using the
+  [regex](https://crates.io/crates/regex) crate.
+* `tokio`; enables the `find_async`, `find_all_async`, and `find_executable_async` methods, and the
+  `matches_async` stream, which check the file system on the blocking thread pool of a
+  [tokio](https://crates.io/crates/tokio) runtime.
 
 # Example
 
@@ -27,7 +43,7 @@ use std::path::PathBuf;
 
 fn which_command(cmd_name: &str) -> Option<PathBuf> {
     let search_path = SearchPath::new("PATH").expect("How do you live with no $PATH?");
-    search_path.find_file(&PathBuf::from(cmd_name))
+    search_path.find_executable(&PathBuf::from(cmd_name))
 }
 ```
 
@@ -53,22 +69,102 @@ fn which_command(cmd_name: &str) -> Option<PathBuf> {
     unused_results,
 )]
 
+use std::collections::{HashMap, HashSet};
 use std::env;
-use std::error::Error;
+use std::ffi::{OsStr, OsString};
 use std::fmt::{Display, Formatter};
+use std::io;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+#[cfg(unix)]
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
 // ------------------------------------------------------------------------------------------------
 
+pub use cached::{CacheOptions, CachedSearchPath};
+
+pub use entry::Entry;
+
+pub use error::Error;
+
+pub use extensions::Extensions;
+
+pub use filesystem::{FileSystem, FileType, Metadata, OsFileSystem};
+
+pub use index::{Index, IndexBuilder};
+
+pub use parse::{EmptyEntries, ParseOptions, PathStyle, StyledDisplay};
+
+pub use lookup::{Lookup, LookupOptions};
+
+pub use matcher::Matcher;
+
+pub use negative::{NegativeCache, NegativeCacheStats};
+
+pub use trace::{Candidate, Outcome, Trace};
+
+pub use walk::WalkOptions;
+
+#[cfg(target_os = "linux")]
+pub use watch::{WatchEvent, WatchGuard};
+
+#[cfg(feature = "regex")]
+pub use regex_match::RegexMatch;
+
+#[cfg(feature = "tokio")]
+pub use asynchronous::MatchStream;
+
 ///
 /// This is the search path itself, it wraps a list of file paths which can then be used to find
 /// file system entries. See the [module](index.html) description for an overview.
 ///
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default)]
 pub struct SearchPath {
-    paths: Vec<PathBuf>,
+    entries: Vec<Entry>,
+    extensions: Extensions,
+    index: Option<Arc<Index>>,
+    negative_cache: Option<Arc<NegativeCache>>,
+    lookup_options: Option<LookupOptions>,
+    unhealthy: Arc<Unhealthy>,
+    generation: u64,
+}
+
+///
+/// The kind of file system entry a lookup is interested in, see
+/// [`SearchPath::find_all_of_kind`](struct.SearchPath.html#method.find_all_of_kind). This type
+/// also implements [`Matcher`](matcher/trait.Matcher.html) and so may be composed with other
+/// matchers.
+///
+/// Except for `Symlink`, kinds describe the target of a symbolic link rather than the link
+/// itself. The `Fifo`, `Socket`, `BlockDevice`, and `CharDevice` kinds only match on Unix
+/// platforms.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EntryKind {
+    /// Any file system entry.
+    Any,
+    /// Only files, including symbolic links to files.
+    File,
+    /// Only directories, including symbolic links to directories.
+    Directory,
+    /// Only files the current user is permitted to execute, see
+    /// [`SearchPath::find_executable`](struct.SearchPath.html#method.find_executable).
+    Executable,
+    /// Only symbolic links, whose target exists.
+    Symlink,
+    /// Only named pipes.
+    Fifo,
+    /// Only Unix domain sockets.
+    Socket,
+    /// Only block devices.
+    BlockDevice,
+    /// Only character devices.
+    CharDevice,
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -83,70 +179,114 @@ const PATH_SEPARATOR_CHAR: char = ':';
 
 const CURRENT_DIR_PATH: &str = ".";
 
-// ------------------------------------------------------------------------------------------------
+const OWNER_EXECUTE: u32 = 0o100;
+const GROUP_EXECUTE: u32 = 0o010;
+const OTHER_EXECUTE: u32 = 0o001;
 
-#[derive(Copy, Clone, Debug, PartialEq)]
-enum FindKind {
-    Any,
-    File,
-    Directory,
+#[cfg(unix)]
+#[derive(Debug)]
+struct EffectiveIds {
+    uid: u32,
+    gid: u32,
+    groups: Vec<u32>,
 }
 
 // ------------------------------------------------------------------------------------------------
 // Public Functions
 // ------------------------------------------------------------------------------------------------
 
+///
+/// Return the first executable file named `cmd_name` found in the search path constructed from
+/// the `PATH` environment variable, or `None`. If `PATH` is not present this returns `None`.
+///
+/// ```rust
+/// use search_path::which;
+///
+/// let shell = which("sh");
+/// ```
+///
+pub fn which(cmd_name: &str) -> Option<PathBuf> {
+    SearchPath::new_or_default("PATH").find_executable(Path::new(cmd_name))
+}
+
 // ------------------------------------------------------------------------------------------------
 // Implementations
 // ------------------------------------------------------------------------------------------------
 
-impl Default for SearchPath {
-    fn default() -> Self {
-        Self {
-            paths: Default::default(),
-        }
-    }
-}
-
-impl Display for SearchPath {
+impl Display for EntryKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
             "{}",
-            self.paths
-                .iter()
-                .map(|p| p.to_string_lossy().to_string())
-                .collect::<Vec<String>>()
-                .join(&PATH_SEPARATOR_CHAR.to_string())
+            match self {
+                EntryKind::Any => "any",
+                EntryKind::File => "file",
+                EntryKind::Directory => "directory",
+                EntryKind::Executable => "executable",
+                EntryKind::Symlink => "symbolic link",
+                EntryKind::Fifo => "FIFO",
+                EntryKind::Socket => "socket",
+                EntryKind::BlockDevice => "block device",
+                EntryKind::CharDevice => "character device",
+            }
         )
     }
 }
 
+// ------------------------------------------------------------------------------------------------
+
+// The negative cache, the lookup options, the set of unhealthy entries, and the generation, are
+// not compared.
+impl PartialEq for SearchPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+            && self.extensions == other.extensions
+            && self.index == other.index
+    }
+}
+
+impl Display for SearchPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_with_style(PathStyle::native()))
+    }
+}
+
 impl From<PathBuf> for SearchPath {
     fn from(v: PathBuf) -> Self {
-        Self { paths: vec![v] }
+        Self::from(vec![v])
     }
 }
 
 impl From<Vec<PathBuf>> for SearchPath {
     fn from(vs: Vec<PathBuf>) -> Self {
-        Self { paths: vs }
+        Self::from(vs.into_iter().map(Entry::from).collect::<Vec<Entry>>())
     }
 }
 
-impl From<Vec<&Path>> for SearchPath {
-    fn from(vs: Vec<&Path>) -> Self {
+impl From<Vec<Entry>> for SearchPath {
+    fn from(vs: Vec<Entry>) -> Self {
         Self {
-            paths: vs.iter().map(|p| PathBuf::from(p)).collect(),
+            entries: vs,
+            extensions: Default::default(),
+            index: None,
+            negative_cache: None,
+            lookup_options: None,
+            unhealthy: Default::default(),
+            generation: next_generation(),
         }
     }
 }
 
+impl From<Vec<&Path>> for SearchPath {
+    fn from(vs: Vec<&Path>) -> Self {
+        Self::from(vs.iter().map(PathBuf::from).collect::<Vec<PathBuf>>())
+    }
+}
+
 impl From<Vec<&str>> for SearchPath {
     fn from(vs: Vec<&str>) -> Self {
-        Self {
-            paths: vs
-                .iter()
+        Self::from(
+            vs.iter()
                 .filter_map(|p| {
                     if p.trim().is_empty() {
                         None
@@ -154,8 +294,8 @@ impl From<Vec<&str>> for SearchPath {
                         Some(PathBuf::from(p))
                     }
                 })
-                .collect(),
-        }
+                .collect::<Vec<PathBuf>>(),
+        )
     }
 }
 
@@ -167,13 +307,29 @@ impl From<String> for SearchPath {
 
 impl From<&str> for SearchPath {
     fn from(s: &str) -> Self {
-        Self::from(s.split(PATH_SEPARATOR_CHAR).collect::<Vec<&str>>())
+        Self::parse(s, &ParseOptions::default())
+    }
+}
+
+impl From<OsString> for SearchPath {
+    fn from(s: OsString) -> Self {
+        Self::from(s.as_os_str())
+    }
+}
+
+impl From<&OsStr> for SearchPath {
+    fn from(s: &OsStr) -> Self {
+        Self::from(
+            env::split_paths(s)
+                .filter(|p| !p.as_os_str().is_empty())
+                .collect::<Vec<PathBuf>>(),
+        )
     }
 }
 
 impl From<SearchPath> for Vec<PathBuf> {
     fn from(p: SearchPath) -> Self {
-        p.paths
+        p.entries.into_iter().map(PathBuf::from).collect()
     }
 }
 
@@ -182,7 +338,7 @@ impl IntoIterator for SearchPath {
     type IntoIter = std::vec::IntoIter<PathBuf>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.paths.into_iter()
+        Vec::from(self).into_iter()
     }
 }
 
@@ -193,7 +349,7 @@ impl SearchPath {
     /// character on other platforms.
     ///
     /// If the environment variable is not present, or could not be read this function returns
-    /// an error.
+    /// an [`Error`](enum.Error.html) describing the failure.
     ///
     /// ```rust,should_panic
     /// use search_path::SearchPath;
@@ -204,20 +360,114 @@ impl SearchPath {
     /// Constructors do not check for duplicate paths, to remove duplicates see the
     /// [`dedup`](struct.SearchPath.html#method.dedup)) method.
     ///
-    pub fn new(env_var: &str) -> Result<Self, Box<dyn Error>> {
+    pub fn new(env_var: &str) -> Result<Self, Error> {
         match env::var(env_var) {
             Ok(path) => Ok(Self::from(path)),
-            Err(e) => Err(Box::new(e)),
+            Err(e) => Err(Error::from_var_error(env_var, e)),
         }
     }
 
     ///
     /// Construct a new search path by parsing the environment variable named `PATH`.
     ///
-    pub fn path() -> Result<Self, Box<dyn Error>> {
+    pub fn path() -> Result<Self, Error> {
         Self::new("PATH")
     }
 
+    ///
+    /// Construct a new search path by parsing the environment variable named `env_var` into
+    /// separate paths, without requiring the value to be valid Unicode. The value is split using
+    /// the same rules as [`std::env::split_paths`], so entries that are not valid UTF-8 are
+    /// preserved exactly.
+    ///
+    /// If the environment variable is not present this function returns an
+    /// [`Error::NotPresent`](enum.Error.html#variant.NotPresent) error.
+    ///
+    /// ```rust,should_panic
+    /// use search_path::SearchPath;
+    ///
+    /// let search_path = SearchPath::new_os("CMD_PATH").expect("No $CMD_PATH present");
+    /// ```
+    ///
+    /// Constructors do not check for duplicate paths, to remove duplicates see the
+    /// [`dedup`](struct.SearchPath.html#method.dedup)) method.
+    ///
+    pub fn new_os(env_var: &str) -> Result<Self, Error> {
+        match env::var_os(env_var) {
+            Some(path) => Ok(Self::from(path)),
+            None => Err(Error::NotPresent {
+                name: env_var.to_string(),
+            }),
+        }
+    }
+
+    ///
+    /// Construct a new search path by parsing the environment variable named `env_var` into
+    /// separate paths, using `options` to determine how empty components are handled. As with
+    /// [`new_os`](struct.SearchPath.html#method.new_os) the value is not required to be valid
+    /// Unicode.
+    ///
+    /// If the environment variable is not present this function returns an
+    /// [`Error::NotPresent`](enum.Error.html#variant.NotPresent) error.
+    ///
+    /// Constructors do not check for duplicate paths, to remove duplicates see the
+    /// [`dedup`](struct.SearchPath.html#method.dedup)) method.
+    ///
+    pub fn new_with_options(env_var: &str, options: &ParseOptions) -> Result<Self, Error> {
+        match env::var_os(env_var) {
+            Some(path) => Ok(Self::from(options.split(&path))),
+            None => Err(Error::NotPresent {
+                name: env_var.to_string(),
+            }),
+        }
+    }
+
+    ///
+    /// Construct a new search path by parsing the string `s` into separate paths, using
+    /// `options` to determine the path style and how empty components are handled.
+    ///
+    /// ```rust
+    /// use search_path::{ParseOptions, SearchPath};
+    /// use std::path::PathBuf;
+    ///
+    /// # #[cfg(not(target_family = "windows"))]
+    /// # {
+    /// let search_path = SearchPath::parse(":/bin", &ParseOptions::posix());
+    /// assert_eq!(search_path.iter().next(), Some(&PathBuf::from(".")));
+    /// # }
+    /// ```
+    ///
+    /// Constructors do not check for duplicate paths, to remove duplicates see the
+    /// [`dedup`](struct.SearchPath.html#method.dedup)) method.
+    ///
+    pub fn parse(s: &str, options: &ParseOptions) -> Self {
+        Self::from(options.split(OsStr::new(s)))
+    }
+
+    ///
+    /// Construct a new search path by parsing the string `s` into separate paths using the
+    /// syntax determined by `style` rather than the syntax of the current platform. Empty
+    /// components are ignored, as with the `From<&str>` implementation.
+    ///
+    /// ```rust
+    /// use search_path::{PathStyle, SearchPath};
+    /// use std::path::PathBuf;
+    ///
+    /// let search_path = SearchPath::parse_with_style(
+    ///     r#"C:\Windows;"C:\Program Files;Tools\bin""#,
+    ///     PathStyle::Windows,
+    /// );
+    /// assert_eq!(search_path.len(), 2);
+    /// assert!(search_path.contains(&PathBuf::from(r"C:\Program Files;Tools\bin")));
+    /// ```
+    ///
+    /// Constructors do not check for duplicate paths, to remove duplicates see the
+    /// [`dedup`](struct.SearchPath.html#method.dedup)) method.
+    ///
+    pub fn parse_with_style(s: &str, style: PathStyle) -> Self {
+        Self::parse(s, &ParseOptions::default().with_style(style))
+    }
+
     ///
     /// Construct a new search path by parsing the environment variable named `env_var` into
     /// separate paths. Paths are separated by the `';'` character on Windows, and the `':'`
@@ -271,36 +521,147 @@ impl SearchPath {
     /// `None`.
     ///
     pub fn find(&self, file_name: &Path) -> Option<PathBuf> {
-        self.find_something(file_name, FindKind::Any)
+        self.find_somethings(file_name, EntryKind::Any, true).pop()
     }
 
     ///
     /// Return all the file system entities, either file or directory, found in the search path.
     ///
     pub fn find_all(&self, file_name: &Path) -> Vec<PathBuf> {
-        let mut results: Vec<PathBuf> = Default::default();
-        for path in &self.paths {
-            let mut path = PathBuf::from(path);
-            path.push(file_name);
-            if path.exists() {
-                results.push(path);
-            }
-        }
-        results
+        self.find_somethings(file_name, EntryKind::Any, false)
+    }
+
+    ///
+    /// Return all the _files_ found in the search path.
+    ///
+    pub fn find_all_files(&self, file_name: &Path) -> Vec<PathBuf> {
+        self.find_somethings(file_name, EntryKind::File, false)
+    }
+
+    ///
+    /// Return all the _directories_ found in the search path.
+    ///
+    pub fn find_all_directories(&self, file_name: &Path) -> Vec<PathBuf> {
+        self.find_somethings(file_name, EntryKind::Directory, false)
+    }
+
+    ///
+    /// Return all the file system entities of the given `kind` found in the search path.
+    ///
+    /// ```rust
+    /// use search_path::{EntryKind, SearchPath};
+    /// use std::path::Path;
+    ///
+    /// let search_path = SearchPath::new_or("RUN_PATH", "/run:/var/run");
+    /// let sockets = search_path.find_all_of_kind(Path::new("docker.sock"), EntryKind::Socket);
+    /// ```
+    ///
+    pub fn find_all_of_kind(&self, file_name: &Path, kind: EntryKind) -> Vec<PathBuf> {
+        self.find_somethings(file_name, kind, false)
+    }
+
+    ///
+    /// Return an iterator over all the file system entities, either file or directory, found in
+    /// the search path. Unlike [`find_all`](struct.SearchPath.html#method.find_all) each
+    /// directory is only checked when the iterator reaches it, so adapters such as `take`, `nth`,
+    /// and `find_map` stop checking once they have the result they need.
+    ///
+    /// ```rust
+    /// use search_path::SearchPath;
+    /// use std::path::Path;
+    ///
+    /// let search_path = SearchPath::new_or_default("PATH");
+    /// let first_two: Vec<_> = search_path.matches(Path::new("python3")).take(2).collect();
+    /// ```
+    ///
+    pub fn matches<'a>(&'a self, file_name: &Path) -> impl Iterator<Item = PathBuf> + 'a {
+        self.matches_something(file_name, EntryKind::Any)
+    }
+
+    ///
+    /// Return an iterator over all the _files_ found in the search path, see
+    /// [`matches`](struct.SearchPath.html#method.matches) for details.
+    ///
+    pub fn matches_files<'a>(&'a self, file_name: &Path) -> impl Iterator<Item = PathBuf> + 'a {
+        self.matches_something(file_name, EntryKind::File)
+    }
+
+    ///
+    /// Return an iterator over all the _directories_ found in the search path, see
+    /// [`matches`](struct.SearchPath.html#method.matches) for details.
+    ///
+    pub fn matches_dirs<'a>(&'a self, file_name: &Path) -> impl Iterator<Item = PathBuf> + 'a {
+        self.matches_something(file_name, EntryKind::Directory)
     }
 
     ///
     /// Return the first _file_ found in the search path, or `None`.
     ///
     pub fn find_file(&self, file_name: &Path) -> Option<PathBuf> {
-        self.find_something(file_name, FindKind::File)
+        self.find_somethings(file_name, EntryKind::File, true).pop()
     }
 
     ///
     /// Return the first _directory_ found in the search path, or `None`.
     ///
     pub fn find_directory(&self, file_name: &Path) -> Option<PathBuf> {
-        self.find_something(file_name, FindKind::Directory)
+        self.find_somethings(file_name, EntryKind::Directory, true)
+            .pop()
+    }
+
+    ///
+    /// Return the first _executable file_ found in the search path, or `None`. On Unix platforms
+    /// a file is executable if the permission bits allow execution by the current effective user
+    /// or group; directories and broken symbolic links are never returned. On other platforms this
+    /// is the same as [`find_file`](struct.SearchPath.html#method.find_file).
+    ///
+    pub fn find_executable(&self, file_name: &Path) -> Option<PathBuf> {
+        self.find_somethings(file_name, EntryKind::Executable, true)
+            .pop()
+    }
+
+    ///
+    /// Return all the _executable files_ found in the search path, see
+    /// [`find_executable`](struct.SearchPath.html#method.find_executable) for details.
+    ///
+    pub fn find_all_executables(&self, file_name: &Path) -> Vec<PathBuf> {
+        self.find_somethings(file_name, EntryKind::Executable, false)
+    }
+
+    ///
+    /// Return the first file system entity, either file or directory, whose name matches the
+    /// regular expression `regex` in the search path, or `None`. Within each directory in the
+    /// search path entries are considered in sorted order. The expression is matched against the
+    /// entry name only, and is not anchored; use `^` and `$` to match the entire name.
+    ///
+    /// This method is only available with the `regex` feature.
+    ///
+    /// ```rust
+    /// use regex::Regex;
+    /// use search_path::SearchPath;
+    ///
+    /// let search_path = SearchPath::new_or_default("PATH");
+    /// let plugin_regex = Regex::new(r"^git-(?P<plugin>.+)$").unwrap();
+    /// for found in search_path.find_all_regex(&plugin_regex) {
+    ///     println!("{} => {:?}", found.name("plugin").unwrap(), found.path());
+    /// }
+    /// ```
+    ///
+    #[cfg(feature = "regex")]
+    pub fn find_regex(&self, regex: &regex::Regex) -> Option<RegexMatch> {
+        self.find_regex_in(&OsFileSystem, regex)
+    }
+
+    ///
+    /// Return all the file system entities, either file or directory, whose name matches the
+    /// regular expression `regex` in the search path. Results are in search path order and,
+    /// within each directory in the search path, sorted.
+    ///
+    /// This method is only available with the `regex` feature.
+    ///
+    #[cfg(feature = "regex")]
+    pub fn find_all_regex(&self, regex: &regex::Regex) -> Vec<RegexMatch> {
+        self.find_all_regex_in(&OsFileSystem, regex)
     }
 
     ///
@@ -309,25 +670,644 @@ impl SearchPath {
     /// will also return `None`.
     ///
     pub fn find_if_name_only(&self, file_name: &Path) -> Option<PathBuf> {
-        if let Some(_) = file_name.parent() {
+        if file_name.parent().is_some() {
             self.find(file_name)
         } else {
             None
         }
     }
 
-    fn find_something(&self, file_name: &Path, kind: FindKind) -> Option<PathBuf> {
-        for path in &self.paths {
-            let mut path = PathBuf::from(path);
-            path.push(file_name);
-            if (kind == FindKind::Any && path.exists())
-                || (kind == FindKind::File && path.is_file())
-                || (kind == FindKind::Directory && path.is_dir())
+    ///
+    /// Return the first file system entity, either file or directory, found in the search path or
+    /// in any sub-directory of an entry in the search path, to a depth of `max_depth`, or `None`.
+    ///
+    /// Each entry in the search path is walked breadth-first, with sub-directories visited in
+    /// sorted order, before moving on to the next entry; so a match anywhere below an earlier
+    /// entry takes precedence over any match below a later one. Symbolic links to directories are
+    /// followed, but no directory is visited twice.
+    ///
+    /// ```rust
+    /// use search_path::SearchPath;
+    /// use std::path::Path;
+    ///
+    /// let search_path = SearchPath::new_or("TEMPLATE_PATH", "templates");
+    /// let template = search_path.find_recursive(Path::new("base.html"), 4);
+    /// ```
+    ///
+    pub fn find_recursive(&self, file_name: &Path, max_depth: usize) -> Option<PathBuf> {
+        self.find_recursive_with(file_name, &WalkOptions::default().with_max_depth(max_depth))
+    }
+
+    ///
+    /// Return the first file system entity, either file or directory, found in the search path or
+    /// in any sub-directory of an entry in the search path, as determined by `options`, or
+    /// `None`. See [`find_recursive`](struct.SearchPath.html#method.find_recursive) for details.
+    ///
+    pub fn find_recursive_with(&self, file_name: &Path, options: &WalkOptions) -> Option<PathBuf> {
+        let request = self.request(file_name, EntryKind::Any, true);
+        self.lookup(&request.with_walk(options))
+            .into_iter()
+            .flatten()
+            .next()
+    }
+
+    ///
+    /// Return all the file system entities, either file or directory, found in the search path or
+    /// in any sub-directory of an entry in the search path, to a depth of `max_depth`. Results
+    /// are in the order described for
+    /// [`find_recursive`](struct.SearchPath.html#method.find_recursive).
+    ///
+    pub fn find_all_recursive(&self, file_name: &Path, max_depth: usize) -> Vec<PathBuf> {
+        self.find_all_recursive_with(file_name, &WalkOptions::default().with_max_depth(max_depth))
+    }
+
+    ///
+    /// Return all the file system entities, either file or directory, found in the search path or
+    /// in any sub-directory of an entry in the search path, as determined by `options`.
+    ///
+    pub fn find_all_recursive_with(&self, file_name: &Path, options: &WalkOptions) -> Vec<PathBuf> {
+        let request = self.request(file_name, EntryKind::Any, false);
+        self.lookup(&request.with_walk(options))
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    ///
+    /// Return the first file system entity, either file or directory, that matches the glob
+    /// `pattern` in the search path, or `None`. Within each directory in the search path matches
+    /// are sorted, so the result is the first in sorted order of the first directory with any
+    /// matches.
+    ///
+    /// The pattern may contain path separators, and supports `?`, `*`, character classes such as
+    /// `[a-z]`, alternatives such as `{a,b}`, and `**` to match zero or more directories. As is the
+    /// case for shells, a leading `.` in a file name must be matched explicitly. The pattern is
+    /// matched below each directory, and so if it is an absolute path this method returns an
+    /// [`Error::InvalidPattern`](enum.Error.html#variant.InvalidPattern) error.
+    ///
+    /// ```rust
+    /// use search_path::SearchPath;
+    ///
+    /// let search_path = SearchPath::new_or("LD_LIBRARY_PATH", "/usr/lib");
+    /// let library = search_path.find_glob("libc.so.*").unwrap();
+    /// assert!(search_path.find_glob("/usr/*/libc.so.*").is_err());
+    /// ```
+    ///
+    pub fn find_glob(&self, pattern: &str) -> Result<Option<PathBuf>, Error> {
+        self.find_glob_in(&OsFileSystem, pattern)
+    }
+
+    ///
+    /// Return all the file system entities, either file or directory, that match the glob
+    /// `pattern` in the search path. Results are in search path order and, within each directory
+    /// in the search path, sorted. See [`find_glob`](struct.SearchPath.html#method.find_glob) for
+    /// the pattern syntax.
+    ///
+    pub fn find_all_glob(&self, pattern: &str) -> Result<Vec<PathBuf>, Error> {
+        self.find_all_glob_in(&OsFileSystem, pattern)
+    }
+
+    ///
+    /// Return the first file system entity, either file or directory, found in the search path,
+    /// or `None`. Unlike [`find`](struct.SearchPath.html#method.find) this method does not treat
+    /// unexpected I/O errors, such as permission denied, as if the entity did not exist. Such
+    /// errors are either collected in the returned `Lookup`, or, if `options` is strict, returned
+    /// immediately.
+    ///
+    /// ```rust
+    /// use search_path::{LookupOptions, SearchPath};
+    /// use std::path::Path;
+    ///
+    /// let search_path = SearchPath::new_or_default("PATH");
+    /// let lookup = search_path.try_find(Path::new("sh"), &LookupOptions::default()).unwrap();
+    /// for error in lookup.errors() {
+    ///     eprintln!("warning: {}", error);
+    /// }
+    /// let found = lookup.into_result();
+    /// ```
+    ///
+    pub fn try_find(
+        &self,
+        file_name: &Path,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Option<PathBuf>>, Error> {
+        self.try_find_something(file_name, EntryKind::Any, options)
+    }
+
+    ///
+    /// Return the first _file_ found in the search path, or `None`, reporting unexpected I/O
+    /// errors as described for [`try_find`](struct.SearchPath.html#method.try_find).
+    ///
+    pub fn try_find_file(
+        &self,
+        file_name: &Path,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Option<PathBuf>>, Error> {
+        self.try_find_something(file_name, EntryKind::File, options)
+    }
+
+    ///
+    /// Return the first _directory_ found in the search path, or `None`, reporting unexpected I/O
+    /// errors as described for [`try_find`](struct.SearchPath.html#method.try_find).
+    ///
+    pub fn try_find_directory(
+        &self,
+        file_name: &Path,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Option<PathBuf>>, Error> {
+        self.try_find_something(file_name, EntryKind::Directory, options)
+    }
+
+    ///
+    /// Return the first _executable file_ found in the search path, or `None`, reporting
+    /// unexpected I/O errors as described for [`try_find`](struct.SearchPath.html#method.try_find).
+    ///
+    pub fn try_find_executable(
+        &self,
+        file_name: &Path,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Option<PathBuf>>, Error> {
+        self.try_find_something(file_name, EntryKind::Executable, options)
+    }
+
+    ///
+    /// Return all the file system entities, either file or directory, found in the search path,
+    /// reporting unexpected I/O errors as described for
+    /// [`try_find`](struct.SearchPath.html#method.try_find).
+    ///
+    pub fn try_find_all(
+        &self,
+        file_name: &Path,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Vec<PathBuf>>, Error> {
+        Ok(self
+            .try_lookup(&self.request(file_name, EntryKind::Any, false), options)?
+            .map(|found| found.into_iter().flatten().collect()))
+    }
+
+    fn try_find_something(
+        &self,
+        file_name: &Path,
+        kind: EntryKind,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Option<PathBuf>>, Error> {
+        Ok(self
+            .try_lookup(&self.request(file_name, kind, true), options)?
+            .map(|found| found.into_iter().flatten().next()))
+    }
+
+    ///
+    /// Perform a lookup for `file_name` of the given `kind`, returning a `Trace` that records
+    /// every candidate path considered, in search order, and the outcome of checking it. The
+    /// path found, if any, is the same as the corresponding _find_ method would return.
+    ///
+    /// ```rust
+    /// use search_path::{EntryKind, SearchPath};
+    /// use std::path::Path;
+    ///
+    /// let search_path = SearchPath::new_or_default("PATH");
+    /// let trace = search_path.explain(Path::new("sh"), EntryKind::Executable);
+    /// println!("{}", trace);
+    /// ```
+    ///
+    pub fn explain(&self, file_name: &Path, kind: EntryKind) -> Trace {
+        self.explain_in(&OsFileSystem, file_name, kind)
+    }
+
+    ///
+    /// Return the first file system entity in any directory in the search path that is selected
+    /// by `matcher`, or `None`. Within each directory in the search path entries are considered
+    /// in sorted order.
+    ///
+    /// ```rust
+    /// use search_path::matcher::{Matcher, Name};
+    /// use search_path::{EntryKind, SearchPath};
+    ///
+    /// let search_path = SearchPath::new_or_default("PATH");
+    /// let python = search_path.find_matching(&EntryKind::Executable.and(Name::new("python3*")));
+    /// ```
+    ///
+    pub fn find_matching<M: Matcher>(&self, matcher: &M) -> Option<PathBuf> {
+        self.find_all_matching(matcher).next()
+    }
+
+    ///
+    /// Return an iterator over all the file system entities in all the directories in the search
+    /// path that are selected by `matcher`. Results are in search path order and, within each
+    /// directory in the search path, sorted. Each directory is only read when the iterator
+    /// reaches it, so a caller that stops early does not pay for the remaining directories.
+    ///
+    pub fn find_all_matching<'a, M: Matcher>(
+        &'a self,
+        matcher: &'a M,
+    ) -> impl Iterator<Item = PathBuf> + 'a {
+        self.find_all_matching_in(&OsFileSystem, matcher)
+    }
+
+    ///
+    /// Return the first file system entity, either file or directory, found in the search path for
+    /// each of `names`. The result maps each name that was found to its first match, names that
+    /// were not found are not present in the result.
+    ///
+    /// Rather than checking every candidate path for every name, as repeated calls to
+    /// [`find`](struct.SearchPath.html#method.find) would, each directory in the search path is
+    /// read once and only those names it contains are checked. Names are compared with the
+    /// directory listing ignoring ASCII case, so that a name is still checked on a file system
+    /// that ignores case; other differences, such as in Unicode normalization, are not detected
+    /// and such names are not found even where `find` would find them. Search order precedence is
+    /// the same as for `find`, and any negative cache or budget attached to the search path is
+    /// used in the same way; so a budget with a deadline skips entries marked as unhealthy, and
+    /// counts each directory read as one probe.
+    ///
+    /// ```rust
+    /// use search_path::SearchPath;
+    ///
+    /// let search_path = SearchPath::new_or_default("PATH");
+    /// let tools = search_path.find_many(&["cc", "ld", "ar", "make"]);
+    /// for (name, path) in &tools {
+    ///     println!("{:?} => {:?}", name, path);
+    /// }
+    /// ```
+    ///
+    pub fn find_many<I, P>(&self, names: I) -> HashMap<PathBuf, PathBuf>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.find_many_of_kind(names, EntryKind::Any)
+    }
+
+    ///
+    /// Return the first file system entity of the given `kind` found in the search path for each
+    /// of `names`, see [`find_many`](struct.SearchPath.html#method.find_many) for details.
+    ///
+    /// ```rust
+    /// use search_path::{EntryKind, SearchPath};
+    ///
+    /// let search_path = SearchPath::new_or_default("PATH");
+    /// let tools = search_path.find_many_of_kind(&["cc", "ld"], EntryKind::Executable);
+    /// ```
+    ///
+    pub fn find_many_of_kind<I, P>(&self, names: I, kind: EntryKind) -> HashMap<PathBuf, PathBuf>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.scan_many(names, kind, true, self.usable_negative_cache(), |request| {
+            self.lookup(request)
+        })
+        .into_iter()
+        .map(|(name, mut paths)| (name, paths.swap_remove(0)))
+        .collect()
+    }
+
+    ///
+    /// Return all the file system entities, either file or directory, found in the search path for
+    /// each of `names`. The result maps each name that was found to its matches, in search path
+    /// order, names that were not found are not present in the result. See
+    /// [`find_many`](struct.SearchPath.html#method.find_many) for details.
+    ///
+    pub fn find_all_many<I, P>(&self, names: I) -> HashMap<PathBuf, Vec<PathBuf>>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.find_all_many_of_kind(names, EntryKind::Any)
+    }
+
+    ///
+    /// Return all the file system entities of the given `kind` found in the search path for each
+    /// of `names`, see [`find_all_many`](struct.SearchPath.html#method.find_all_many) for details.
+    ///
+    pub fn find_all_many_of_kind<I, P>(
+        &self,
+        names: I,
+        kind: EntryKind,
+    ) -> HashMap<PathBuf, Vec<PathBuf>>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.scan_many(
+            names,
+            kind,
+            false,
+            self.usable_negative_cache(),
+            |request| self.lookup(request),
+        )
+    }
+
+    // Return the matches for each of `names` that was found, as performed by `lookup`, reading
+    // each directory once. Any `cache` is used for the local disk only.
+    fn scan_many<I, P, L>(
+        &self,
+        names: I,
+        kind: EntryKind,
+        first_only: bool,
+        cache: Option<&NegativeCache>,
+        lookup: L,
+    ) -> HashMap<PathBuf, Vec<PathBuf>>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+        L: FnOnce(&Request) -> Vec<Vec<PathBuf>>,
+    {
+        let mut seen: HashSet<PathBuf> = Default::default();
+        let mut pending: Vec<PathBuf> = Vec::new();
+        for name in names {
+            let name = name.as_ref();
+            if seen.insert(name.to_path_buf())
+                && !matches!(cache, Some(cache) if cache.contains(self, name, kind))
             {
-                return Some(path);
+                pending.push(name.to_path_buf());
             }
         }
-        None
+        if pending.is_empty() {
+            return Default::default();
+        }
+        let request = Request::many(
+            pending
+                .iter()
+                .map(|name| self.extensions.candidates(name))
+                .collect(),
+            kind,
+            first_only,
+        );
+        // The times are read first so that a change made during the search is detected.
+        let searched: Vec<(PathBuf, Option<SystemTime>)> = match cache {
+            Some(_) => self
+                .directories(&OsFileSystem)
+                .map(|dir| {
+                    let modified = modified_time(&OsFileSystem, &dir);
+                    (dir, modified)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        let found = lookup(&request);
+        if let Some(cache) = cache {
+            for (name, paths) in pending.iter().zip(&found) {
+                if paths.is_empty() {
+                    cache.insert(self, name, kind, searched.clone());
+                }
+            }
+        }
+        pending
+            .into_iter()
+            .zip(found)
+            .filter(|(_, paths)| !paths.is_empty())
+            .collect()
+    }
+
+    fn find_somethings(&self, file_name: &Path, kind: EntryKind, first_only: bool) -> Vec<PathBuf> {
+        match self.usable_negative_cache() {
+            Some(cache) => self.find_negative_cached(cache, file_name, kind, first_only),
+            None => self
+                .lookup(&self.request(file_name, kind, first_only))
+                .into_iter()
+                .flatten()
+                .collect(),
+        }
+    }
+
+    // The negative cache is not used within a budget, as validating it would access the file
+    // system outside of the budget.
+    fn usable_negative_cache(&self) -> Option<&NegativeCache> {
+        match &self.lookup_options {
+            Some(_) => None,
+            None => self.negative_cache.as_deref(),
+        }
+    }
+
+    fn find_negative_cached(
+        &self,
+        cache: &NegativeCache,
+        file_name: &Path,
+        kind: EntryKind,
+        first_only: bool,
+    ) -> Vec<PathBuf> {
+        if cache.contains(self, file_name, kind) {
+            return Vec::new();
+        }
+        let names = self.extensions.candidates(file_name);
+        let mut searched = Vec::new();
+        let mut found = Vec::new();
+        'entries: for entry in &self.entries {
+            for path in entry.directories(&OsFileSystem, self.index.as_deref(), Some(&names)) {
+                // The time is read first so that a change made during the search is detected.
+                let modified = modified_time(&OsFileSystem, &path);
+                for name in &names {
+                    let candidate = path.join(name);
+                    if is_match_path(&OsFileSystem, &kind, &candidate) {
+                        found.push(candidate);
+                        if first_only {
+                            break 'entries;
+                        }
+                    }
+                }
+                searched.push((path, modified));
+            }
+        }
+        if found.is_empty() {
+            cache.insert(self, file_name, kind, searched);
+        }
+        found
+    }
+
+    fn matches_something<'a>(
+        &'a self,
+        file_name: &Path,
+        kind: EntryKind,
+    ) -> Box<dyn Iterator<Item = PathBuf> + 'a> {
+        match &self.lookup_options {
+            Some(_) => Box::new(
+                self.lookup(&self.request(file_name, kind, false))
+                    .into_iter()
+                    .flatten(),
+            ),
+            None => Box::new(self.checked_candidates(&OsFileSystem, file_name, kind)),
+        }
+    }
+
+    fn checked_candidates<'a, F: FileSystem + ?Sized>(
+        &'a self,
+        file_system: &'a F,
+        file_name: &Path,
+        kind: EntryKind,
+    ) -> impl Iterator<Item = PathBuf> + 'a {
+        self.candidates(file_system, file_name)
+            .filter(move |path| is_match_path(file_system, &kind, path))
+    }
+
+    fn candidates<'a, F: FileSystem + ?Sized>(
+        &'a self,
+        file_system: &'a F,
+        file_name: &Path,
+    ) -> impl Iterator<Item = PathBuf> + 'a {
+        let names = Rc::new(self.extensions.candidates(file_name));
+        self.entries.iter().flat_map(move |entry| {
+            let names = names.clone();
+            entry
+                .directories(file_system, self.index.as_deref(), Some(&names))
+                .flat_map(move |path| names.iter().map(|name| path.join(name)).collect::<Vec<_>>())
+        })
+    }
+
+    fn directories<'a, F: FileSystem + ?Sized>(
+        &'a self,
+        file_system: &'a F,
+    ) -> impl Iterator<Item = PathBuf> + 'a {
+        self.entries
+            .iter()
+            .flat_map(move |entry| entry.directories(file_system, self.index.as_deref(), None))
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    ///
+    /// Set the list of file extensions used by the _find_ methods. When this list is not empty,
+    /// each directory in the search path is checked for the name as given and then for the name
+    /// with each extension appended, in order, before moving on to the next directory. This is
+    /// akin to the mechanism Windows uses to find executables using the `PATHEXT` environment
+    /// variable, but works on any platform.
+    ///
+    /// ```rust
+    /// use search_path::{Extensions, SearchPath};
+    /// use std::path::Path;
+    ///
+    /// let mut search_path = SearchPath::new_or_default("PATH");
+    /// search_path.set_extensions(Extensions::new_or("TOOL_EXT", ".sh;.py"));
+    /// let tool = search_path.find_executable(Path::new("tool"));
+    /// ```
+    ///
+    pub fn set_extensions(&mut self, extensions: Extensions) {
+        self.extensions = extensions;
+        self.generation = next_generation();
+    }
+
+    ///
+    /// Return the list of file extensions used by the _find_ methods.
+    ///
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    ///
+    /// Attach an index to this search path. For each entry in the search path that is covered by
+    /// one of the index's root entries the _find_ methods only consider the directories the index
+    /// lists for that entry, and for a name only those directories the index records as containing
+    /// it; candidates are still checked on disk. If the index records none of the names looked
+    /// up for an entry, or does not cover the entry at all, the entry is searched on disk as
+    /// usual, except for index-only entries which are only ever searched using the index.
+    ///
+    /// The index is used as-is, see [`Index::is_stale`](struct.Index.html#method.is_stale) to
+    /// determine whether it should be rebuilt.
+    ///
+    pub fn set_index(&mut self, index: Index) {
+        self.index = Some(Arc::new(index));
+        self.generation = next_generation();
+    }
+
+    ///
+    /// Detach any index from this search path, the _find_ methods will only search on disk.
+    ///
+    pub fn remove_index(&mut self) {
+        self.index = None;
+        self.generation = next_generation();
+    }
+
+    ///
+    /// Return the index attached to this search path, if any.
+    ///
+    pub fn index(&self) -> Option<&Index> {
+        self.index.as_deref()
+    }
+
+    ///
+    /// Attach a negative cache to this search path, so that the _find_ methods answer repeated
+    /// lookups that found nothing without checking any candidates, see
+    /// [`NegativeCache`](struct.NegativeCache.html) for details. The cache is shared with any
+    /// clone of this search path.
+    ///
+    pub fn set_negative_cache(&mut self, cache: NegativeCache) {
+        self.negative_cache = Some(Arc::new(cache))
+    }
+
+    ///
+    /// Detach any negative cache from this search path.
+    ///
+    pub fn remove_negative_cache(&mut self) {
+        self.negative_cache = None
+    }
+
+    ///
+    /// Return the negative cache attached to this search path, if any.
+    ///
+    pub fn negative_cache(&self) -> Option<&NegativeCache> {
+        self.negative_cache.as_deref()
+    }
+
+    ///
+    /// Attach a budget to this search path, so that the _find_ and _find_all_ methods for a name,
+    /// along with the _matches_, _find_recursive_, and _find_many_ methods, run within it as
+    /// described for [`LookupOptions`](struct.LookupOptions.html). These methods do not report
+    /// errors, and so return whatever was found before the budget ran out, the strict option is
+    /// ignored. An entry that times out is marked as unhealthy if `options` has a cool down
+    /// period. The _matches_ methods find all their results before returning, rather than as the
+    /// iterator reaches each directory, and any negative cache is not used, as validating it would
+    /// access the file system outside of the budget. The _glob_, _matching_, and _regex_ methods
+    /// do not use the budget.
+    ///
+    /// ```rust
+    /// use search_path::{LookupOptions, SearchPath};
+    /// use std::path::Path;
+    /// use std::time::Duration;
+    ///
+    /// let mut search_path = SearchPath::new_or_default("PATH");
+    /// search_path.set_lookup_options(
+    ///     LookupOptions::default()
+    ///         .with_deadline(Duration::from_secs(1))
+    ///         .with_cool_down(Duration::from_secs(60)),
+    /// );
+    /// let shell = search_path.find_executable(Path::new("sh"));
+    /// ```
+    ///
+    pub fn set_lookup_options(&mut self, options: LookupOptions) {
+        self.lookup_options = Some(options.with_strict(false))
+    }
+
+    ///
+    /// Detach any budget from this search path.
+    ///
+    pub fn remove_lookup_options(&mut self) {
+        self.lookup_options = None
+    }
+
+    ///
+    /// Return the budget attached to this search path, if any.
+    ///
+    pub fn lookup_options(&self) -> Option<&LookupOptions> {
+        self.lookup_options.as_ref()
+    }
+
+    ///
+    /// Return the entries, in search order, that are marked as unhealthy because the deadline of
+    /// a lookup ran out while searching them, and whose cool down period has not yet passed; see
+    /// [`LookupOptions::with_cool_down`](struct.LookupOptions.html#method.with_cool_down). The
+    /// set of unhealthy entries is shared with any clone of this search path.
+    ///
+    pub fn unhealthy_entries(&self) -> Vec<PathBuf> {
+        self.iter()
+            .filter(|path| self.unhealthy.contains(path))
+            .cloned()
+            .collect()
+    }
+
+    ///
+    /// Clear the set of unhealthy entries, so that they are searched by the next lookup.
+    ///
+    pub fn clear_unhealthy(&self) {
+        self.unhealthy.clear()
     }
 
     // --------------------------------------------------------------------------------------------
@@ -336,21 +1316,21 @@ impl SearchPath {
     /// Return `true` if this instance has no paths to search, else `false`.
     ///
     pub fn is_empty(&self) -> bool {
-        self.paths.is_empty()
+        self.entries.is_empty()
     }
 
     ///
     /// Return the current number of paths in the list of paths to search.
     ///
     pub fn len(&self) -> usize {
-        self.paths.len()
+        self.entries.len()
     }
 
     ///
     /// Return `true` if the list of paths to search contains the `path` value, else `false`.
     ///
     pub fn contains(&self, path: &PathBuf) -> bool {
-        self.paths.contains(path)
+        self.entries.iter().any(|entry| entry.path() == path)
     }
 
     ///
@@ -365,7 +1345,102 @@ impl SearchPath {
     /// Return an iterator over all the paths in the list of paths to search.
     ///
     pub fn iter(&self) -> impl Iterator<Item = &PathBuf> {
-        self.paths.iter()
+        self.entries.iter().map(Entry::path)
+    }
+
+    ///
+    /// Return an iterator over all the entries in the list of paths to search. Unlike
+    /// [`iter`](struct.SearchPath.html#method.iter) this includes the flags set on each entry,
+    /// see [`ParseOptions::with_kpathsea`](struct.ParseOptions.html#method.with_kpathsea).
+    ///
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    ///
+    /// Return a value that implements `Display` by joining the list of paths to search using the
+    /// syntax determined by `style`. For the `Windows` style any path containing the `';'`
+    /// character is enclosed in double quotes. As with the `Display` implementation for
+    /// `SearchPath` paths that are not valid Unicode are converted lossily.
+    ///
+    /// ```rust
+    /// use search_path::{PathStyle, SearchPath};
+    ///
+    /// let search_path = SearchPath::parse_with_style(r"C:\Windows;C:\Tools", PathStyle::Windows);
+    /// assert_eq!(
+    ///     search_path.display_with_style(PathStyle::Windows).to_string(),
+    ///     r"C:\Windows;C:\Tools"
+    /// );
+    /// ```
+    ///
+    pub fn display_with_style(&self, style: PathStyle) -> StyledDisplay<'_> {
+        StyledDisplay::new(&self.entries, style)
+    }
+
+    ///
+    /// Return the list of paths to search as a single value, joined with the platform specific
+    /// path separator, suitable for setting as the value of an environment variable for a child
+    /// process. Unlike the `Display` implementation this conversion is lossless. Only the path of
+    /// each entry is included, any kpathsea markers are left out as the operating system does not
+    /// understand them; see
+    /// [`to_kpathsea_os_string`](struct.SearchPath.html#method.to_kpathsea_os_string) to keep them.
+    ///
+    /// If any path contains the path separator character, or a NUL character, it cannot be
+    /// represented and this method returns an
+    /// [`Error::InvalidEntry`](enum.Error.html#variant.InvalidEntry) error for the first such path.
+    ///
+    /// ```rust
+    /// use search_path::SearchPath;
+    /// use std::process::Command;
+    ///
+    /// let mut search_path = SearchPath::new_or_default("PATH");
+    /// search_path.prepend_cwd();
+    /// let mut command = Command::new("ls");
+    /// let _ = command.env("PATH", search_path.to_os_string().unwrap());
+    /// ```
+    ///
+    pub fn to_os_string(&self) -> Result<OsString, Error> {
+        self.to_env_value()
+    }
+
+    ///
+    /// Return the list of paths to search as the value of an environment variable, this is the
+    /// same as [`to_os_string`](struct.SearchPath.html#method.to_os_string).
+    ///
+    /// ```rust
+    /// use search_path::SearchPath;
+    /// use std::path::PathBuf;
+    ///
+    /// let search_path = SearchPath::from(vec![PathBuf::from("a"), PathBuf::from("b")]);
+    /// assert_eq!(
+    ///     search_path.to_env_value().unwrap(),
+    ///     std::env::join_paths(&["a", "b"]).unwrap()
+    /// );
+    /// ```
+    ///
+    pub fn to_env_value(&self) -> Result<OsString, Error> {
+        join_entries(&self.entries, |entry| {
+            entry.path().as_os_str().to_os_string()
+        })
+    }
+
+    ///
+    /// Return the list of paths to search as a single value in kpathsea syntax, as for
+    /// [`to_os_string`](struct.SearchPath.html#method.to_os_string) but with a trailing `//` on
+    /// recursive entries and a leading `!!` on index-only entries, so that it may be parsed again
+    /// with [`ParseOptions::with_kpathsea`](struct.ParseOptions.html#method.with_kpathsea).
+    ///
+    /// ```rust
+    /// use search_path::{ParseOptions, SearchPath};
+    ///
+    /// let options = ParseOptions::default().with_kpathsea(true);
+    /// let search_path = SearchPath::parse("/usr/share/texmf//", &options);
+    /// assert_eq!(search_path.to_os_string().unwrap(), "/usr/share/texmf");
+    /// assert_eq!(search_path.to_kpathsea_os_string().unwrap(), "/usr/share/texmf//");
+    /// ```
+    ///
+    pub fn to_kpathsea_os_string(&self) -> Result<OsString, Error> {
+        join_entries(&self.entries, Entry::to_kpathsea)
     }
 
     // --------------------------------------------------------------------------------------------
@@ -376,7 +1451,8 @@ impl SearchPath {
     /// [`dedup`](struct.SearchPath.html#method.dedup)) method.
     ///
     pub fn append(&mut self, path: PathBuf) {
-        self.paths.push(path)
+        self.entries.push(Entry::from(path));
+        self.generation = next_generation();
     }
 
     ///
@@ -394,7 +1470,8 @@ impl SearchPath {
     /// [`dedup`](struct.SearchPath.html#method.dedup)) method.
     ///
     pub fn prepend(&mut self, path: PathBuf) {
-        self.paths.insert(0, path)
+        self.entries.insert(0, Entry::from(path));
+        self.generation = next_generation();
     }
 
     ///
@@ -411,7 +1488,8 @@ impl SearchPath {
     /// was not in the list.
     ///
     pub fn remove(&mut self, path: &PathBuf) {
-        self.paths.retain(|p| p != path);
+        self.entries.retain(|entry| entry.path() != path);
+        self.generation = next_generation();
     }
 
     ///
@@ -422,6 +1500,214 @@ impl SearchPath {
     pub fn dedup(&mut self) {
         use std::collections::HashSet;
         let mut seen: HashSet<PathBuf> = Default::default();
-        self.paths.retain(|p| seen.insert(p.clone()))
+        self.entries
+            .retain(|entry| seen.insert(entry.path().clone()));
+        self.generation = next_generation();
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn join_entries<F: Fn(&Entry) -> OsString>(
+    entries: &[Entry],
+    to_os_string: F,
+) -> Result<OsString, Error> {
+    for path in entries.iter().map(Entry::path) {
+        let lossy = path.to_string_lossy();
+        if lossy.contains(PATH_SEPARATOR_CHAR) {
+            return Err(Error::invalid_entry(
+                path,
+                format!("contains the path separator {:?}", PATH_SEPARATOR_CHAR),
+            ));
+        } else if lossy.contains('\0') {
+            return Err(Error::invalid_entry(path, "contains a NUL character"));
+        }
+    }
+    env::join_paths(entries.iter().map(to_os_string))
+        .map_err(|e| Error::invalid_entry(PathBuf::new(), e.to_string()))
+}
+
+fn is_match_path<F: FileSystem + ?Sized, M: Matcher + ?Sized>(
+    file_system: &F,
+    matcher: &M,
+    path: &Path,
+) -> bool {
+    // `metadata` follows symbolic links, so a broken link is an error and is skipped here.
+    match file_system.metadata(path) {
+        Ok(metadata) => matcher.is_match(path, &metadata),
+        Err(_) => false,
+    }
+}
+
+fn try_is_match_path<F: FileSystem + ?Sized, M: Matcher + ?Sized>(
+    file_system: &F,
+    matcher: &M,
+    path: &Path,
+) -> io::Result<bool> {
+    match file_system.metadata(path) {
+        Ok(metadata) => Ok(matcher.is_match(path, &metadata)),
+        Err(e) if is_not_found(&e) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+// Return `true` if a file with the permission bits `mode`, owned by the user `uid` and group
+// `gid`, may be executed by the effective user of the process.
+#[cfg(unix)]
+fn is_executable_mode(mode: u32, uid: u32, gid: u32) -> bool {
+    let ids = effective_ids();
+    if ids.uid == 0 {
+        mode & (OWNER_EXECUTE | GROUP_EXECUTE | OTHER_EXECUTE) != 0
+    } else if uid == ids.uid {
+        mode & OWNER_EXECUTE != 0
+    } else if gid == ids.gid || ids.groups.contains(&gid) {
+        mode & GROUP_EXECUTE != 0
+    } else {
+        mode & OTHER_EXECUTE != 0
     }
 }
+
+// There are no effective ids to compare against, so any execute permission bit is enough.
+#[cfg(not(unix))]
+fn is_executable_mode(mode: u32, _: u32, _: u32) -> bool {
+    mode & (OWNER_EXECUTE | GROUP_EXECUTE | OTHER_EXECUTE) != 0
+}
+
+// Return the entries of `dir` within `file_system`, with their names, sorted by name. A directory
+// that cannot be read is treated as empty, in the same way as a missing entry is by the _find_
+// methods.
+fn sorted_entries<F: FileSystem + ?Sized>(file_system: &F, dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut entries: Vec<(String, PathBuf)> = match file_system.read_dir(dir) {
+        Ok(paths) => paths
+            .into_iter()
+            .filter_map(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .map(|name| (name, path.clone()))
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    entries.sort();
+    entries
+}
+
+// Return the bytes of an `OsStr`, as returned by `as_encoded_bytes`, as an `OsStr` again. The bytes
+// must only have been split immediately before or after an ASCII character.
+#[allow(unsafe_code)]
+fn encoded_os_str(bytes: &[u8]) -> &OsStr {
+    // SAFETY: callers only split encoded bytes at ASCII characters, which the encoding guarantees
+    // to be valid boundaries.
+    unsafe { OsStr::from_encoded_bytes_unchecked(bytes) }
+}
+
+// Return a generation number not used by any other search path, assigned whenever a search path
+// is created or its entries, extensions, or index change. Clones share the generation of the
+// search path they were cloned from, so that equal generations imply the same lookup results.
+fn next_generation() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    NEXT.fetch_add(1, Ordering::Relaxed)
+}
+
+// Return the modification time of the entry at `path`, or `None` if it cannot be read, for
+// example because it does not exist.
+fn modified_time<F: FileSystem + ?Sized>(file_system: &F, path: &Path) -> Option<SystemTime> {
+    file_system
+        .metadata(path)
+        .ok()
+        .and_then(|metadata| metadata.modified())
+}
+
+// Return the name as a single path component, if it is one, such that it may be looked up
+// directly in a directory listing.
+fn single_component(name: &Path) -> Option<&OsStr> {
+    let mut components = name.components();
+    match (components.next(), components.next()) {
+        (Some(std::path::Component::Normal(name)), None) => Some(name),
+        _ => None,
+    }
+}
+
+// A missing entry, a broken symbolic link, or a path through something that is not a directory
+// are all expected when searching, and are not reported as errors.
+fn is_not_found(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::NotFound | io::ErrorKind::NotADirectory
+    )
+}
+
+// Return the effective ids of the process. Reading the supplementary groups takes two system
+// calls, so they are cached and only read again if the effective user or group changes.
+#[cfg(unix)]
+#[allow(unsafe_code)]
+fn effective_ids() -> Arc<EffectiveIds> {
+    static CACHED: Mutex<Option<Arc<EffectiveIds>>> = Mutex::new(None);
+
+    // SAFETY: these calls have no preconditions.
+    let (uid, gid) = unsafe { (libc::geteuid(), libc::getegid()) };
+    let mut cached = CACHED.lock().unwrap_or_else(PoisonError::into_inner);
+    match cached.as_ref() {
+        Some(ids) if ids.uid == uid && ids.gid == gid => ids.clone(),
+        _ => {
+            // SAFETY: `getgroups` is only asked to fill a buffer of the size it reported.
+            let groups = unsafe {
+                let count = libc::getgroups(0, std::ptr::null_mut());
+                let mut groups: Vec<libc::gid_t> = vec![0; count.max(0) as usize];
+                let count = libc::getgroups(count, groups.as_mut_ptr());
+                groups.truncate(count.max(0) as usize);
+                groups
+            };
+            let ids = Arc::new(EffectiveIds { uid, gid, groups });
+            *cached = Some(ids.clone());
+            ids
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Modules
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(feature = "tokio")]
+mod asynchronous;
+
+mod cached;
+
+mod entry;
+
+mod error;
+
+mod extensions;
+
+mod filesystem;
+
+mod glob;
+use glob::Glob;
+
+mod index;
+
+mod lookup;
+use lookup::{Request, Unhealthy};
+
+pub mod matcher;
+
+mod negative;
+
+#[cfg(feature = "parallel")]
+mod parallel;
+
+mod parse;
+
+#[cfg(feature = "regex")]
+mod regex_match;
+
+mod trace;
+
+mod walk;
+use walk::Walk;
+
+#[cfg(target_os = "linux")]
+mod watch;
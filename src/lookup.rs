@@ -0,0 +1,898 @@
+/*!
+Provides the options and result type for lookups that report I/O errors rather than treating them
+as if the entry did not exist, and that may be limited in time or in the number of checks made.
+*/
+
+use crate::{
+    Entry, EntryKind, Error, FileSystem, Index, Metadata, OsFileSystem, SearchPath, Walk,
+    WalkOptions,
+};
+use std::collections::{HashMap, HashSet};
+use std::ffi::{OsStr, OsString};
+use std::io::{self, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The set of options used by the fallible _try_find_ methods, see
+/// [`SearchPath::try_find`](struct.SearchPath.html#method.try_find).
+///
+/// By default lookups are lenient, unexpected I/O errors are collected and the search continues
+/// with the next candidate.
+///
+/// A lookup may also be given a budget, a deadline on the time it may take and a maximum number
+/// of probes it may make, where each check of a candidate, and each directory read or entry
+/// checked while walking a recursive entry, is one probe. When the deadline runs out the lookup
+/// stops with an [`Error::Timeout`](enum.Error.html#variant.Timeout), and when the probes run out
+/// with an [`Error::ProbeLimit`](enum.Error.html#variant.ProbeLimit), either naming the entry
+/// being searched; this is returned for a strict lookup, or else collected and returned along
+/// with anything found so far. A budget may also be attached to a search path, see
+/// [`SearchPath::set_lookup_options`](struct.SearchPath.html#method.set_lookup_options), so that
+/// it applies to the _find_ methods as well.
+///
+/// When a deadline is set, lookups on the local disk check candidates on one of a small set of
+/// worker threads shared by all lookups, so that the lookup returns on time even if a check never
+/// completes, for example on an unresponsive network mount. Such a check is left in flight, and
+/// holds on to its thread until it finishes, if it ever does, while a new worker takes its place
+/// in the set; so each check that never completes costs one thread. Lookups within any other
+/// [`FileSystem`](trait.FileSystem.html) check the deadline before each probe, and so cannot
+/// return before a check that never completes.
+///
+/// ```rust
+/// use search_path::{LookupOptions, SearchPath};
+/// use std::path::Path;
+/// use std::time::Duration;
+///
+/// let search_path = SearchPath::new_or_default("PATH");
+/// let options = LookupOptions::default()
+///     .with_deadline(Duration::from_millis(500))
+///     .with_max_probes(1_000)
+///     .with_cool_down(Duration::from_secs(60));
+/// let shell = search_path.try_find_file(Path::new("sh"), &options);
+/// ```
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LookupOptions {
+    strict: bool,
+    deadline: Option<Duration>,
+    max_probes: Option<usize>,
+    cool_down: Option<Duration>,
+}
+
+///
+/// The result of a fallible lookup, this is the value found along with any non-fatal errors met
+/// along the way.
+///
+#[derive(Debug)]
+pub struct Lookup<T> {
+    pub(crate) result: T,
+    errors: Vec<Error>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A lookup of one or more names, each with the candidate names to check for it in every
+/// directory searched.
+///
+#[derive(Clone, Debug)]
+pub(crate) struct Request {
+    names: Vec<Vec<PathBuf>>,
+    kind: EntryKind,
+    first_only: bool,
+    walk: Option<WalkOptions>,
+    scan: bool,
+}
+
+///
+/// The entries of a search path that timed out, each with the time until which it is skipped.
+///
+#[derive(Debug, Default)]
+pub(crate) struct Unhealthy(Mutex<HashMap<PathBuf, Instant>>);
+
+///
+/// The progress of a lookup, as reported by a `Probe`.
+///
+#[derive(Debug)]
+enum Event {
+    Entry(PathBuf),
+    Checked(usize, PathBuf, io::Result<bool>),
+    Exhausted(usize),
+    Expired(usize),
+}
+
+///
+/// The part of a lookup that accesses the file system, which may be run on a worker thread.
+///
+#[derive(Debug)]
+struct Probe<'a, F: ?Sized> {
+    entries: Vec<Entry>,
+    index: Option<Arc<Index>>,
+    request: Request,
+    budget: Arc<Budget<'a, F>>,
+}
+
+///
+/// A file system, as seen by a `Probe`, counting each access as a probe and refusing any once
+/// the maximum number of probes has been made or the deadline has passed.
+///
+#[derive(Debug)]
+struct Budget<'a, F: ?Sized> {
+    file_system: &'a F,
+    probes: AtomicUsize,
+    max_probes: Option<usize>,
+    deadline: Option<Instant>,
+    refused: AtomicBool,
+}
+
+///
+/// The worker threads shared by all lookups with a deadline on the local disk, created as needed
+/// up to `MAX_WORKERS`. Each worker takes the next job from a single queue. A worker whose job is
+/// abandoned, because its lookup timed out, no longer counts towards `MAX_WORKERS` and exits once
+/// the job finishes, if it ever does.
+///
+#[derive(Debug)]
+struct Workers {
+    sender: Sender<Job>,
+    receiver: Arc<Mutex<Receiver<Job>>>,
+    threads: usize,
+    idle: Arc<AtomicUsize>,
+}
+
+///
+/// A task queued for the workers, along with its state: one of `QUEUED`, `RUNNING`, `DONE`, or
+/// `ABANDONED`.
+///
+struct Job {
+    state: Arc<AtomicU8>,
+    task: Task,
+}
+
+type Task = Box<dyn FnOnce() + Send>;
+
+const QUEUED: u8 = 0;
+const RUNNING: u8 = 1;
+const DONE: u8 = 2;
+const ABANDONED: u8 = 3;
+
+const MAX_WORKERS: usize = 4;
+
+static WORKERS: Mutex<Option<Workers>> = Mutex::new(None);
+
+///
+/// The part of a lookup that collects the events reported by a `Probe`.
+///
+#[derive(Debug)]
+struct Run<'a> {
+    options: &'a LookupOptions,
+    lookup: Lookup<Vec<Vec<PathBuf>>>,
+    entry: PathBuf,
+    started: Instant,
+    timed_out: bool,
+    error: Option<Error>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl LookupOptions {
+    ///
+    /// Return options that abort a lookup on the first unexpected I/O error.
+    ///
+    pub fn strict() -> Self {
+        Self::default().with_strict(true)
+    }
+
+    ///
+    /// Return a copy of these options with strict error handling set to `strict`.
+    ///
+    pub fn with_strict(self, strict: bool) -> Self {
+        Self { strict, ..self }
+    }
+
+    ///
+    /// Return `true` if a lookup should abort on the first unexpected I/O error, else `false`.
+    ///
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    ///
+    /// Return a copy of these options with the time a lookup may take limited to `deadline`.
+    ///
+    pub fn with_deadline(self, deadline: Duration) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    ///
+    /// Return the time a lookup may take, if limited.
+    ///
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline
+    }
+
+    ///
+    /// Return a copy of these options with the number of probes a lookup may make limited to
+    /// `max_probes`.
+    ///
+    pub fn with_max_probes(self, max_probes: usize) -> Self {
+        Self {
+            max_probes: Some(max_probes),
+            ..self
+        }
+    }
+
+    ///
+    /// Return the number of probes a lookup may make, if limited.
+    ///
+    pub fn max_probes(&self) -> Option<usize> {
+        self.max_probes
+    }
+
+    ///
+    /// Return a copy of these options where an entry that was being searched when the deadline
+    /// of a lookup ran out is marked as unhealthy, and skipped by lookups with a deadline, for
+    /// the `cool_down` period; see
+    /// [`SearchPath::unhealthy_entries`](struct.SearchPath.html#method.unhealthy_entries).
+    ///
+    pub fn with_cool_down(self, cool_down: Duration) -> Self {
+        Self {
+            cool_down: Some(cool_down),
+            ..self
+        }
+    }
+
+    ///
+    /// Return the period for which an entry that timed out is skipped, if any.
+    ///
+    pub fn cool_down(&self) -> Option<Duration> {
+        self.cool_down
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T> Lookup<T> {
+    pub(crate) fn new(result: T) -> Self {
+        Self {
+            result,
+            errors: Default::default(),
+        }
+    }
+
+    ///
+    /// Return the value found by the lookup.
+    ///
+    pub fn result(&self) -> &T {
+        &self.result
+    }
+
+    ///
+    /// Return the value found by the lookup, discarding any errors.
+    ///
+    pub fn into_result(self) -> T {
+        self.result
+    }
+
+    ///
+    /// Return `true` if any errors were met during the lookup, else `false`.
+    ///
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    ///
+    /// Return the non-fatal errors met during the lookup, in search order.
+    ///
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    pub(crate) fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Lookup<U> {
+        Lookup {
+            result: f(self.result),
+            errors: self.errors,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Request {
+    ///
+    /// Return a request for a single name, with the candidate `names` for it.
+    ///
+    pub(crate) fn new(names: Vec<PathBuf>, kind: EntryKind, first_only: bool) -> Self {
+        Self {
+            names: vec![names],
+            kind,
+            first_only,
+            walk: None,
+            scan: false,
+        }
+    }
+
+    ///
+    /// Return a request for many names, each with its candidate names, where each directory is
+    /// read once and only the candidates it lists are checked.
+    ///
+    pub(crate) fn many(names: Vec<Vec<PathBuf>>, kind: EntryKind, first_only: bool) -> Self {
+        Self {
+            names,
+            kind,
+            first_only,
+            walk: None,
+            scan: true,
+        }
+    }
+
+    ///
+    /// Return a copy of this request where every entry is walked using `options`, as for the
+    /// _find_recursive_ methods.
+    ///
+    pub(crate) fn with_walk(self, options: &WalkOptions) -> Self {
+        Self {
+            walk: Some(options.clone()),
+            ..self
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl SearchPath {
+    ///
+    /// Return a request for `file_name` of the given `kind`, with its candidate names.
+    ///
+    pub(crate) fn request(&self, file_name: &Path, kind: EntryKind, first_only: bool) -> Request {
+        Request::new(self.extensions.candidates(file_name), kind, first_only)
+    }
+
+    ///
+    /// Perform `request` on the local disk, within the budget attached to this search path if
+    /// any, returning the matches for each of its names in turn.
+    ///
+    pub(crate) fn lookup(&self, request: &Request) -> Vec<Vec<PathBuf>> {
+        let default = LookupOptions::default();
+        let options = self.lookup_options.as_ref().unwrap_or(&default);
+        self.try_lookup(request, options)
+            .map(Lookup::into_result)
+            .unwrap_or_default()
+    }
+
+    ///
+    /// Perform `request` within `file_system`, and within the budget attached to this search
+    /// path if any, returning the matches for each of its names in turn.
+    ///
+    pub(crate) fn lookup_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        request: &Request,
+    ) -> Vec<Vec<PathBuf>> {
+        let default = LookupOptions::default();
+        let options = self.lookup_options.as_ref().unwrap_or(&default);
+        self.try_lookup_in(file_system, request, options)
+            .map(Lookup::into_result)
+            .unwrap_or_default()
+    }
+
+    ///
+    /// Perform a fallible lookup for `request` on the local disk, within the budget set in
+    /// `options`. If there is a deadline candidates are checked on a worker thread, so that the
+    /// lookup returns on time even if a check never completes.
+    ///
+    pub(crate) fn try_lookup(
+        &self,
+        request: &Request,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Vec<Vec<PathBuf>>>, Error> {
+        self.lookup_with(
+            &OsFileSystem,
+            request,
+            options,
+            |probe, run| match options.deadline {
+                Some(deadline) => probe.run_until(deadline, run),
+                None => probe.run(&mut |event| run.handle(event)),
+            },
+        )
+    }
+
+    ///
+    /// Perform a fallible lookup for `request` within `file_system`, within the budget set in
+    /// `options`. The deadline is only checked before each probe.
+    ///
+    pub(crate) fn try_lookup_in<F: FileSystem + ?Sized>(
+        &self,
+        file_system: &F,
+        request: &Request,
+        options: &LookupOptions,
+    ) -> Result<Lookup<Vec<Vec<PathBuf>>>, Error> {
+        self.lookup_with(file_system, request, options, |probe, run| {
+            probe.run(&mut |event| run.handle(event))
+        })
+    }
+
+    fn lookup_with<'a, F, R>(
+        &self,
+        file_system: &'a F,
+        request: &Request,
+        options: &LookupOptions,
+        run_probe: R,
+    ) -> Result<Lookup<Vec<Vec<PathBuf>>>, Error>
+    where
+        F: FileSystem + ?Sized,
+        R: FnOnce(Probe<'a, F>, &mut Run<'_>),
+    {
+        let entries: Vec<Entry> = self
+            .entries
+            .iter()
+            .filter(|entry| request.walk.is_none() || !entry.is_index_only())
+            .filter(|entry| options.deadline.is_none() || !self.unhealthy.contains(entry.path()))
+            .cloned()
+            .collect();
+        let mut run = match entries.first() {
+            Some(entry) => Run::new(options, entry.path().clone(), request.names.len()),
+            None => return Ok(Lookup::new(vec![Vec::new(); request.names.len()])),
+        };
+        let probe = Probe {
+            entries,
+            index: self.index.clone(),
+            request: request.clone(),
+            budget: Arc::new(Budget {
+                file_system,
+                probes: AtomicUsize::new(0),
+                max_probes: options.max_probes,
+                deadline: options.deadline.map(|deadline| run.started + deadline),
+                refused: AtomicBool::new(false),
+            }),
+        };
+        run_probe(probe, &mut run);
+        if let (true, Some(cool_down)) = (run.timed_out, options.cool_down) {
+            self.unhealthy.insert(run.entry.clone(), cool_down);
+        }
+        run.finish()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Unhealthy {
+    pub(crate) fn contains(&self, entry: &Path) -> bool {
+        self.lock().contains_key(entry)
+    }
+
+    pub(crate) fn clear(&self) {
+        self.lock().clear()
+    }
+
+    fn insert(&self, entry: PathBuf, cool_down: Duration) {
+        let _ = self.lock().insert(entry, Instant::now() + cool_down);
+    }
+
+    // Entries whose cool down period has passed are removed whenever the set is used.
+    fn lock(&self) -> MutexGuard<'_, HashMap<PathBuf, Instant>> {
+        let mut entries = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+        entries.retain(|_, until| *until > now);
+        entries
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<F: FileSystem + ?Sized> Probe<'_, F> {
+    ///
+    /// Search each entry in turn, reporting progress to `sink`, until every name has been found,
+    /// there are no more directories, the budget runs out, or `sink` returns `false`.
+    ///
+    fn run(&self, sink: &mut dyn FnMut(Event) -> bool) {
+        let budget = &*self.budget;
+        let mut pending: Vec<usize> = (0..self.request.names.len()).collect();
+        for entry in &self.entries {
+            if pending.is_empty() || !sink(Event::Entry(entry.path().clone())) {
+                return;
+            }
+            // Only a lookup for a single name may be limited to the directories the index
+            // records as containing it.
+            let names = match (self.request.scan, self.request.names.first()) {
+                (false, Some(names)) => Some(names.as_slice()),
+                _ => None,
+            };
+            let directories: Box<dyn Iterator<Item = PathBuf>> = match &self.request.walk {
+                Some(options) => Box::new(Walk::new(budget, entry.path(), options)),
+                None => entry.directories(budget, self.index.as_deref(), names),
+            };
+            for dir in directories {
+                if !self.search(&dir, &mut pending, sink) || pending.is_empty() {
+                    return;
+                }
+            }
+            // A walk that was refused may have skipped some directories of this entry.
+            if budget.refused.load(Ordering::Relaxed) {
+                let _ = sink(budget.refused_event());
+                return;
+            }
+        }
+    }
+
+    ///
+    /// Check the candidates in `dir` for each of the `pending` names, removing those that are
+    /// found if only the first match is wanted. Return `false` if the lookup should stop.
+    ///
+    fn search(
+        &self,
+        dir: &Path,
+        pending: &mut Vec<usize>,
+        sink: &mut dyn FnMut(Event) -> bool,
+    ) -> bool {
+        let budget = &*self.budget;
+        // A scanned directory is read once and only the candidates it lists are checked. If it
+        // cannot be listed, but does exist, each candidate is checked.
+        let listing: Option<HashSet<OsString>> = if self.request.scan {
+            match budget.read_dir(dir) {
+                Ok(paths) => Some(
+                    paths
+                        .iter()
+                        .filter_map(|path| path.file_name().map(OsStr::to_ascii_lowercase))
+                        .collect(),
+                ),
+                Err(e) if crate::is_not_found(&e) => return true,
+                Err(_) => None,
+            }
+        } else {
+            None
+        };
+        let mut i = 0;
+        while i < pending.len() {
+            let mut found = false;
+            for name in &self.request.names[pending[i]] {
+                let listed = match (&listing, crate::single_component(name)) {
+                    (Some(listing), Some(key)) => listing.contains(&key.to_ascii_lowercase()),
+                    _ => true,
+                };
+                if !listed {
+                    continue;
+                }
+                if budget.is_exhausted() || budget.is_expired() {
+                    let _ = sink(budget.refused_event());
+                    return false;
+                }
+                let path = dir.join(name);
+                let result = crate::try_is_match_path(budget, &self.request.kind, &path);
+                found = matches!(result, Ok(true));
+                if !sink(Event::Checked(pending[i], path, result)) {
+                    return false;
+                }
+                if found && self.request.first_only {
+                    break;
+                }
+            }
+            if found && self.request.first_only {
+                let _ = pending.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+        true
+    }
+}
+
+impl Probe<'static, OsFileSystem> {
+    ///
+    /// Run this probe on a worker thread, collecting its events into `run` until it completes or
+    /// `deadline` has passed since the lookup started. If the deadline passes while the probe is
+    /// still running it is abandoned, and its worker replaced.
+    ///
+    fn run_until(self, deadline: Duration, run: &mut Run<'_>) {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let worker_cancelled = cancelled.clone();
+        let budget = self.budget.clone();
+        // A probe whose lookup has already returned stops before its next check.
+        let task: Task = Box::new(move || {
+            self.run(&mut |event| {
+                !worker_cancelled.load(Ordering::Relaxed) && sender.send(event).is_ok()
+            })
+        });
+        // If no worker thread can be created the probe is run on this thread instead, its events
+        // are then queued until it completes.
+        let state = match Workers::submit(task) {
+            Ok(state) => Some(state),
+            Err(task) => {
+                task();
+                None
+            }
+        };
+        loop {
+            // Once the deadline has passed no further events are taken, even those already
+            // queued, so a lookup never returns results found after its deadline.
+            let remaining = deadline
+                .checked_sub(run.started.elapsed())
+                .unwrap_or_default();
+            let event = match remaining.is_zero() {
+                true => Err(RecvTimeoutError::Timeout),
+                false => receiver.recv_timeout(remaining),
+            };
+            match event {
+                Ok(event) => {
+                    if !run.handle(event) {
+                        break;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    run.timed_out(budget.probes());
+                    if let Some(state) = &state {
+                        Workers::abandon(state);
+                    }
+                    break;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<F: FileSystem + ?Sized> FileSystem for Budget<'_, F> {
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        self.spend()?;
+        self.file_system.metadata(path)
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata> {
+        self.spend()?;
+        self.file_system.symlink_metadata(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        self.spend()?;
+        self.file_system.read_dir(path)
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read>> {
+        self.spend()?;
+        self.file_system.open(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.spend()?;
+        self.file_system.canonicalize(path)
+    }
+}
+
+impl<F: ?Sized> Budget<'_, F> {
+    fn is_exhausted(&self) -> bool {
+        matches!(self.max_probes, Some(max_probes) if self.probes() >= max_probes)
+    }
+
+    fn is_expired(&self) -> bool {
+        matches!(self.deadline, Some(deadline) if Instant::now() >= deadline)
+    }
+
+    fn probes(&self) -> usize {
+        self.probes.load(Ordering::Relaxed)
+    }
+
+    // The event that stops a lookup once this budget has run out.
+    fn refused_event(&self) -> Event {
+        if self.is_expired() {
+            Event::Expired(self.probes())
+        } else {
+            Event::Exhausted(self.probes())
+        }
+    }
+
+    fn spend(&self) -> io::Result<()> {
+        if self.is_exhausted() {
+            self.refused.store(true, Ordering::Relaxed);
+            return Err(io::Error::other(
+                "the maximum number of probes has been made",
+            ));
+        }
+        if self.is_expired() {
+            self.refused.store(true, Ordering::Relaxed);
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the deadline of the lookup has passed",
+            ));
+        }
+        let _ = self.probes.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Workers {
+    ///
+    /// Queue `task` to be run by the next free worker, creating a new worker if none is free and
+    /// there are fewer than `MAX_WORKERS`, and return the state of its job. The task is returned
+    /// if there are no workers at all.
+    ///
+    fn submit(task: Task) -> Result<Arc<AtomicU8>, Task> {
+        let mut workers = WORKERS.lock().unwrap_or_else(PoisonError::into_inner);
+        let workers = workers.get_or_insert_with(Workers::new);
+        if workers.idle.load(Ordering::Relaxed) == 0 && workers.threads < MAX_WORKERS {
+            workers.spawn();
+        }
+        if workers.threads == 0 {
+            return Err(task);
+        }
+        let state = Arc::new(AtomicU8::new(QUEUED));
+        let job = Job {
+            state: state.clone(),
+            task,
+        };
+        match workers.sender.send(job) {
+            Ok(()) => Ok(state),
+            Err(e) => Err(e.0.task),
+        }
+    }
+
+    ///
+    /// Abandon the job with `state`. A job that has not started is skipped, while the worker
+    /// running a job that has started is no longer counted, so that a new worker is created in
+    /// its place; it exits once the job finishes.
+    ///
+    fn abandon(state: &AtomicU8) {
+        loop {
+            let current = state.load(Ordering::Acquire);
+            if current != QUEUED && current != RUNNING {
+                return;
+            }
+            if state
+                .compare_exchange(current, ABANDONED, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                if current == RUNNING {
+                    let mut workers = WORKERS.lock().unwrap_or_else(PoisonError::into_inner);
+                    if let Some(workers) = workers.as_mut() {
+                        workers.threads -= 1;
+                    }
+                }
+                return;
+            }
+        }
+    }
+
+    fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            threads: 0,
+            idle: Default::default(),
+        }
+    }
+
+    fn spawn(&mut self) {
+        let receiver = self.receiver.clone();
+        let idle = self.idle.clone();
+        if thread::Builder::new()
+            .name("search_path-lookup".to_string())
+            .spawn(move || loop {
+                let _ = idle.fetch_add(1, Ordering::Relaxed);
+                let job = receiver
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .recv();
+                let _ = idle.fetch_sub(1, Ordering::Relaxed);
+                let job: Job = match job {
+                    Ok(job) => job,
+                    Err(_) => return,
+                };
+                if job
+                    .state
+                    .compare_exchange(QUEUED, RUNNING, Ordering::AcqRel, Ordering::Acquire)
+                    .is_err()
+                {
+                    continue;
+                }
+                // A job that panics must not take its worker with it.
+                drop(panic::catch_unwind(AssertUnwindSafe(job.task)));
+                // A worker whose job was abandoned has already been replaced.
+                if job
+                    .state
+                    .compare_exchange(RUNNING, DONE, Ordering::AcqRel, Ordering::Acquire)
+                    .is_err()
+                {
+                    return;
+                }
+            })
+            .is_ok()
+        {
+            self.threads += 1;
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<'a> Run<'a> {
+    fn new(options: &'a LookupOptions, entry: PathBuf, names: usize) -> Self {
+        Self {
+            options,
+            lookup: Lookup::new(vec![Vec::new(); names]),
+            entry,
+            started: Instant::now(),
+            timed_out: false,
+            error: None,
+        }
+    }
+
+    ///
+    /// Record `event`, returning `false` if the lookup should stop.
+    ///
+    fn handle(&mut self, event: Event) -> bool {
+        match event {
+            Event::Entry(entry) => {
+                self.entry = entry;
+                true
+            }
+            Event::Checked(name, path, result) => {
+                match result {
+                    Ok(true) => self.lookup.result[name].push(path),
+                    Ok(false) => {}
+                    Err(e) => return self.failed(Error::io(path, e)),
+                }
+                true
+            }
+            Event::Exhausted(probes) => {
+                let error = Error::ProbeLimit {
+                    entry: self.entry.clone(),
+                    probes,
+                };
+                let _ = self.failed(error);
+                false
+            }
+            Event::Expired(probes) => {
+                self.timed_out(probes);
+                false
+            }
+        }
+    }
+
+    fn timed_out(&mut self, probes: usize) {
+        self.timed_out = true;
+        let error = Error::Timeout {
+            entry: self.entry.clone(),
+            probes,
+            elapsed: self.started.elapsed(),
+        };
+        let _ = self.failed(error);
+    }
+
+    fn failed(&mut self, error: Error) -> bool {
+        if self.options.strict {
+            self.error = Some(error);
+            false
+        } else {
+            self.lookup.errors.push(error);
+            true
+        }
+    }
+
+    fn finish(self) -> Result<Lookup<Vec<Vec<PathBuf>>>, Error> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.lookup),
+        }
+    }
+}
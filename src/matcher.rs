@@ -0,0 +1,356 @@
+/*!
+Provides the `Matcher` trait, used to select file system entries during a lookup, along with a set
+of built-in matchers and the combinators used to compose them.
+
+# Example
+
+The following finds all Rust source files, of at most 4KiB, in the search path.
+
+```rust
+use search_path::matcher::{Extension, Matcher, Size};
+use search_path::{EntryKind, SearchPath};
+
+let search_path = SearchPath::new_or("SRC_PATH", "src");
+let matcher = EntryKind::File
+    .and(Extension::new("rs"))
+    .and(Size::new(..=4096));
+for path in search_path.find_all_matching(&matcher) {
+    println!("{:?}", path);
+}
+```
+*/
+
+use crate::glob::NamePattern;
+use crate::{EntryKind, FileType, Metadata};
+use std::ffi::OsString;
+use std::fmt::Debug;
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
+use std::time::SystemTime;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A predicate over file system entries. The `metadata` passed to a matcher is that of the entry
+/// at `path`, following symbolic links, as returned by
+/// [`FileSystem::metadata`](../trait.FileSystem.html#tymethod.metadata); entries whose metadata
+/// cannot be read, such as broken symbolic links, are never passed to a matcher.
+///
+pub trait Matcher: Debug {
+    ///
+    /// Return `true` if the entry at `path`, with the given `metadata`, is selected by this matcher,
+    /// else `false`.
+    ///
+    fn is_match(&self, path: &Path, metadata: &Metadata) -> bool;
+
+    ///
+    /// Return a matcher that selects entries selected by both this matcher and `other`.
+    ///
+    fn and<M: Matcher>(self, other: M) -> And<Self, M>
+    where
+        Self: Sized,
+    {
+        And(self, other)
+    }
+
+    ///
+    /// Return a matcher that selects entries selected by either this matcher or `other`.
+    ///
+    fn or<M: Matcher>(self, other: M) -> Or<Self, M>
+    where
+        Self: Sized,
+    {
+        Or(self, other)
+    }
+
+    ///
+    /// Return a matcher that selects entries **not** selected by this matcher.
+    ///
+    fn not(self) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not(self)
+    }
+}
+
+///
+/// A matcher that selects entries selected by both of its component matchers, see
+/// [`Matcher::and`](trait.Matcher.html#method.and).
+///
+#[derive(Clone, Debug)]
+pub struct And<A, B>(A, B);
+
+///
+/// A matcher that selects entries selected by either of its component matchers, see
+/// [`Matcher::or`](trait.Matcher.html#method.or).
+///
+#[derive(Clone, Debug)]
+pub struct Or<A, B>(A, B);
+
+///
+/// A matcher that selects entries **not** selected by its component matcher, see
+/// [`Matcher::not`](trait.Matcher.html#method.not).
+///
+#[derive(Clone, Debug)]
+pub struct Not<A>(A);
+
+///
+/// A matcher that selects entries whose file name matches a glob pattern. The pattern syntax is
+/// that of [`SearchPath::find_glob`](../struct.SearchPath.html#method.find_glob), although
+/// only a single path component is matched and so `/` and `**` have no special meaning.
+///
+#[derive(Clone, Debug)]
+pub struct Name(NamePattern);
+
+///
+/// A matcher that selects entries whose file name has a given extension. The comparison is
+/// case-sensitive, and the extension is given without a leading `'.'`.
+///
+#[derive(Clone, Debug)]
+pub struct Extension(OsString);
+
+///
+/// A matcher that selects entries whose size, in bytes, is within a range.
+///
+#[derive(Clone, Debug)]
+pub struct Size(Bound<u64>, Bound<u64>);
+
+///
+/// A matcher that selects entries whose last modification time is within a range. Entries for
+/// which the platform does not record a modification time are never selected.
+///
+#[derive(Clone, Debug)]
+pub struct Modified(Bound<SystemTime>, Bound<SystemTime>);
+
+///
+/// A matcher that selects entries whose permission bits include all of a given set of bits, for
+/// example `0o644`. Entries whose permission bits are not known are never selected.
+///
+/// This matcher is only available on Unix platforms.
+///
+#[cfg(unix)]
+#[derive(Clone, Debug)]
+pub struct Mode(u32);
+
+///
+/// A matcher that selects entries owned by a given user, and optionally group, identifier.
+/// Entries whose owner is not known are never selected.
+///
+/// This matcher is only available on Unix platforms.
+///
+#[cfg(unix)]
+#[derive(Clone, Debug)]
+pub struct Owner {
+    uid: Option<u32>,
+    gid: Option<u32>,
+}
+
+///
+/// A matcher that selects entries that may not be written to.
+///
+#[derive(Clone, Debug)]
+pub struct ReadOnly;
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<M: Matcher + ?Sized> Matcher for &M {
+    fn is_match(&self, path: &Path, metadata: &Metadata) -> bool {
+        (**self).is_match(path, metadata)
+    }
+}
+
+impl<M: Matcher + ?Sized> Matcher for Box<M> {
+    fn is_match(&self, path: &Path, metadata: &Metadata) -> bool {
+        (**self).is_match(path, metadata)
+    }
+}
+
+impl Matcher for EntryKind {
+    fn is_match(&self, _: &Path, metadata: &Metadata) -> bool {
+        match self {
+            EntryKind::Any => true,
+            EntryKind::File => metadata.is_file(),
+            EntryKind::Directory => metadata.is_dir(),
+            EntryKind::Executable => metadata.is_executable(),
+            EntryKind::Symlink => metadata.is_symlink(),
+            EntryKind::Fifo => metadata.file_type() == FileType::Fifo,
+            EntryKind::Socket => metadata.file_type() == FileType::Socket,
+            EntryKind::BlockDevice => metadata.file_type() == FileType::BlockDevice,
+            EntryKind::CharDevice => metadata.file_type() == FileType::CharDevice,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<A: Matcher, B: Matcher> Matcher for And<A, B> {
+    fn is_match(&self, path: &Path, metadata: &Metadata) -> bool {
+        self.0.is_match(path, metadata) && self.1.is_match(path, metadata)
+    }
+}
+
+impl<A: Matcher, B: Matcher> Matcher for Or<A, B> {
+    fn is_match(&self, path: &Path, metadata: &Metadata) -> bool {
+        self.0.is_match(path, metadata) || self.1.is_match(path, metadata)
+    }
+}
+
+impl<A: Matcher> Matcher for Not<A> {
+    fn is_match(&self, path: &Path, metadata: &Metadata) -> bool {
+        !self.0.is_match(path, metadata)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Matcher for Name {
+    fn is_match(&self, path: &Path, _: &Metadata) -> bool {
+        match path.file_name() {
+            Some(name) => self.0.is_match(&name.to_string_lossy()),
+            None => false,
+        }
+    }
+}
+
+impl Name {
+    ///
+    /// Construct a new matcher for the glob `pattern`.
+    ///
+    pub fn new(pattern: &str) -> Self {
+        Self(NamePattern::new(pattern))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Matcher for Extension {
+    fn is_match(&self, path: &Path, _: &Metadata) -> bool {
+        path.extension() == Some(self.0.as_os_str())
+    }
+}
+
+impl Extension {
+    ///
+    /// Construct a new matcher for the file name extension `extension`.
+    ///
+    pub fn new(extension: &str) -> Self {
+        Self(OsString::from(extension.trim_start_matches('.')))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Matcher for Size {
+    fn is_match(&self, _: &Path, metadata: &Metadata) -> bool {
+        (self.0, self.1).contains(&metadata.size())
+    }
+}
+
+impl Size {
+    ///
+    /// Construct a new matcher for sizes within `range`, for example `1024..` or `..=4096`.
+    ///
+    pub fn new<R: RangeBounds<u64>>(range: R) -> Self {
+        Self(range.start_bound().cloned(), range.end_bound().cloned())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Matcher for Modified {
+    fn is_match(&self, _: &Path, metadata: &Metadata) -> bool {
+        match metadata.modified() {
+            Some(modified) => (self.0, self.1).contains(&modified),
+            None => false,
+        }
+    }
+}
+
+impl Modified {
+    ///
+    /// Construct a new matcher for modification times within `range`.
+    ///
+    pub fn new<R: RangeBounds<SystemTime>>(range: R) -> Self {
+        Self(range.start_bound().cloned(), range.end_bound().cloned())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(unix)]
+impl Matcher for Mode {
+    fn is_match(&self, _: &Path, metadata: &Metadata) -> bool {
+        matches!(metadata.mode(), Some(mode) if mode & self.0 == self.0)
+    }
+}
+
+#[cfg(unix)]
+impl Mode {
+    ///
+    /// Construct a new matcher for entries whose permission bits include all of `mode`.
+    ///
+    pub fn new(mode: u32) -> Self {
+        Self(mode)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(unix)]
+impl Matcher for Owner {
+    fn is_match(&self, _: &Path, metadata: &Metadata) -> bool {
+        self.uid
+            .map(|uid| metadata.uid() == Some(uid))
+            .unwrap_or(true)
+            && self
+                .gid
+                .map(|gid| metadata.gid() == Some(gid))
+                .unwrap_or(true)
+    }
+}
+
+#[cfg(unix)]
+impl Owner {
+    ///
+    /// Construct a new matcher for entries owned by the user `uid`.
+    ///
+    pub fn user(uid: u32) -> Self {
+        Self {
+            uid: Some(uid),
+            gid: None,
+        }
+    }
+
+    ///
+    /// Construct a new matcher for entries owned by the group `gid`.
+    ///
+    pub fn group(gid: u32) -> Self {
+        Self {
+            uid: None,
+            gid: Some(gid),
+        }
+    }
+
+    ///
+    /// Construct a new matcher for entries owned by both the user `uid` and group `gid`.
+    ///
+    pub fn new(uid: u32, gid: u32) -> Self {
+        Self {
+            uid: Some(uid),
+            gid: Some(gid),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Matcher for ReadOnly {
+    fn is_match(&self, _: &Path, metadata: &Metadata) -> bool {
+        metadata.is_readonly()
+    }
+}
@@ -0,0 +1,287 @@
+/*!
+Provides a cache of lookups that found nothing, see
+[`NegativeCache`](../struct.NegativeCache.html).
+*/
+
+use crate::{EntryKind, OsFileSystem, SearchPath};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A cache of the names, and kinds, for which the _find_ methods of a search path found nothing,
+/// attached using [`SearchPath::set_negative_cache`](struct.SearchPath.html#method.set_negative_cache).
+/// A repeated lookup of a cached miss is answered without checking any of its candidates.
+///
+/// The modification time of every directory searched by a miss is recorded, and if one of them
+/// changes the cached misses that searched it are discarded, as a new entry in that directory may
+/// satisfy any of them. These times are only checked when a lookup is for a cached miss, and then
+/// only for the directories that miss searched, and only once the validation interval, by default
+/// one second, has passed since a directory was last checked; lookups in between are answered
+/// from memory alone. An interval of zero checks the directories on every lookup answered by the
+/// cache. Changes that do not modify a searched directory, such as the permissions of a file or
+/// the target of a symbolic link, are not detected; use
+/// [`clear`](struct.NegativeCache.html#method.clear) when such changes are expected.
+///
+/// Changing the entries, extensions, or index of the search path also discards all the cached
+/// misses. Clones of a search path share its cache.
+///
+/// ```rust
+/// use search_path::{NegativeCache, SearchPath};
+/// use std::path::Path;
+///
+/// let mut search_path = SearchPath::new_or_default("PATH");
+/// search_path.set_negative_cache(NegativeCache::default());
+/// for _ in 0..3 {
+///     assert_eq!(search_path.find(Path::new("no-such-plugin")), None);
+/// }
+/// let stats = search_path.negative_cache().unwrap().stats();
+/// assert_eq!((stats.hits(), stats.misses()), (2, 1));
+/// ```
+///
+#[derive(Debug)]
+pub struct NegativeCache {
+    validation_interval: Duration,
+    state: Mutex<State>,
+}
+
+///
+/// The statistics collected by a [`NegativeCache`](struct.NegativeCache.html).
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct NegativeCacheStats {
+    hits: u64,
+    misses: u64,
+    invalidations: u64,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+const DEFAULT_VALIDATION_INTERVAL: Duration = Duration::from_secs(1);
+
+///
+/// A directory searched by one or more cached misses.
+///
+#[derive(Debug)]
+struct Directory {
+    modified: Option<SystemTime>,
+    validated_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    generation: Option<u64>,
+    directories: HashMap<PathBuf, Directory>,
+    names: HashMap<(PathBuf, EntryKind), Vec<PathBuf>>,
+    stats: NegativeCacheStats,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Default for NegativeCache {
+    fn default() -> Self {
+        Self {
+            validation_interval: DEFAULT_VALIDATION_INTERVAL,
+            state: Default::default(),
+        }
+    }
+}
+
+impl NegativeCache {
+    ///
+    /// Return a copy of this cache with the modification time of each searched directory only
+    /// checked once `interval` has passed since it was last checked. A zero interval checks them
+    /// on every lookup answered by the cache, the default is one second.
+    ///
+    pub fn with_validation_interval(self, interval: Duration) -> Self {
+        Self {
+            validation_interval: interval,
+            ..self
+        }
+    }
+
+    ///
+    /// Return the interval after which the modification times of searched directories are
+    /// checked again.
+    ///
+    pub fn validation_interval(&self) -> Duration {
+        self.validation_interval
+    }
+
+    ///
+    /// Return the statistics collected by this cache.
+    ///
+    pub fn stats(&self) -> NegativeCacheStats {
+        self.lock().stats
+    }
+
+    ///
+    /// Discard all the cached misses, this is counted as an invalidation if there were any.
+    ///
+    pub fn clear(&self) {
+        self.lock().invalidate()
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    ///
+    /// Return `true`, and record a hit, if a lookup of `file_name` for `kind` in `search_path` is
+    /// known to find nothing, else `false`. Only the directories searched by the cached miss, and
+    /// not checked within the validation interval, are validated; this is done without holding
+    /// the lock.
+    ///
+    pub(crate) fn contains(
+        &self,
+        search_path: &SearchPath,
+        file_name: &Path,
+        kind: EntryKind,
+    ) -> bool {
+        let key = (file_name.to_path_buf(), kind);
+        let expired: Vec<(PathBuf, Option<SystemTime>)> = {
+            let mut state = self.lock();
+            if state.generation != Some(search_path.generation) {
+                state.invalidate();
+                state.generation = Some(search_path.generation);
+            }
+            match state.names.get(&key) {
+                None => return false,
+                Some(searched) => searched
+                    .iter()
+                    .filter_map(|path| {
+                        state
+                            .directories
+                            .get(path)
+                            .filter(|dir| dir.validated_at.elapsed() >= self.validation_interval)
+                            .map(|dir| (path.clone(), dir.modified))
+                    })
+                    .collect(),
+            }
+        };
+        let checked: Vec<(PathBuf, bool)> = expired
+            .into_iter()
+            .map(|(path, modified)| {
+                let changed = crate::modified_time(&OsFileSystem, &path) != modified;
+                (path, changed)
+            })
+            .collect();
+        let mut state = self.lock();
+        if state.generation != Some(search_path.generation) {
+            return false;
+        }
+        let now = Instant::now();
+        for (path, changed) in checked {
+            if changed {
+                state.discard(&path);
+            } else if let Some(dir) = state.directories.get_mut(&path) {
+                dir.validated_at = now;
+            }
+        }
+        if !state.names.contains_key(&key) {
+            return false;
+        }
+        state.stats.hits += 1;
+        true
+    }
+
+    ///
+    /// Record that a lookup of `file_name` for `kind` in `search_path` found nothing after
+    /// searching `directories`, each with the modification time read before it was searched, and
+    /// count it as a miss. A directory whose time differs from the one recorded for it discards
+    /// the cached misses that searched it.
+    ///
+    pub(crate) fn insert(
+        &self,
+        search_path: &SearchPath,
+        file_name: &Path,
+        kind: EntryKind,
+        directories: Vec<(PathBuf, Option<SystemTime>)>,
+    ) {
+        let mut state = self.lock();
+        state.stats.misses += 1;
+        if state.generation != Some(search_path.generation) {
+            return;
+        }
+        let now = Instant::now();
+        let mut searched = Vec::with_capacity(directories.len());
+        for (path, modified) in directories {
+            if matches!(state.directories.get(&path), Some(dir) if dir.modified != modified) {
+                state.discard(&path);
+            }
+            let _ = state.directories.insert(
+                path.clone(),
+                Directory {
+                    modified,
+                    validated_at: now,
+                },
+            );
+            searched.push(path);
+        }
+        let _ = state
+            .names
+            .insert((file_name.to_path_buf(), kind), searched);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl NegativeCacheStats {
+    ///
+    /// Return the number of lookups answered by the cache.
+    ///
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    ///
+    /// Return the number of lookups the cache could not answer, and which found nothing when
+    /// searched. Lookups that found something are not counted.
+    ///
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    ///
+    /// Return the number of times the cached misses were discarded, because a searched directory
+    /// or the search path changed, or the cache was cleared.
+    ///
+    pub fn invalidations(&self) -> u64 {
+        self.invalidations
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl State {
+    fn invalidate(&mut self) {
+        if !self.names.is_empty() {
+            self.stats.invalidations += 1;
+        }
+        self.names.clear();
+        self.directories.clear();
+    }
+
+    // Discard the record of the directory at `path`, along with the cached misses that searched
+    // it, counting this as an invalidation if there were any.
+    fn discard(&mut self, path: &Path) {
+        let _ = self.directories.remove(path);
+        let before = self.names.len();
+        self.names
+            .retain(|_, searched| !searched.iter().any(|dir| dir == path));
+        if self.names.len() != before {
+            self.stats.invalidations += 1;
+        }
+    }
+}
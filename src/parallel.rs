@@ -0,0 +1,237 @@
+/*!
+Provides lookups that probe every entry of a search path concurrently, see
+[`SearchPath::find_parallel`](../struct.SearchPath.html#method.find_parallel).
+*/
+
+use crate::{Entry, EntryKind, Index, OsFileSystem, SearchPath};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// One of the worker threads of a lookup, each probes the next entry in the search path not yet
+/// taken by any worker until there are none left.
+///
+#[derive(Clone, Debug)]
+struct Probe {
+    entries: Arc<Vec<Entry>>,
+    index: Option<Arc<Index>>,
+    names: Arc<Vec<PathBuf>>,
+    kind: EntryKind,
+    first_only: bool,
+    state: Arc<State>,
+    sender: Sender<(usize, Vec<PathBuf>)>,
+}
+
+///
+/// The state shared between all the probes of a single lookup.
+///
+#[derive(Debug)]
+struct State {
+    next_entry: AtomicUsize,
+    earliest_match: AtomicUsize,
+    finished: AtomicBool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl SearchPath {
+    ///
+    /// Return the first file system entity, either file or directory, found in the search path,
+    /// or `None`, as [`find`](struct.SearchPath.html#method.find) but probing the entries in the
+    /// search path concurrently. Entries are taken in search order by a fixed set of worker
+    /// threads, one for each unit of available parallelism but never more than there are entries.
+    ///
+    /// The result is always that of the earliest matching entry in search order, and is returned
+    /// as soon as it is known; that is once a match is confirmed and every earlier entry has been
+    /// probed, without waiting for any later entries. Once a match is confirmed probes of later
+    /// entries stop before their next check, although a check already in progress, for example on
+    /// an unresponsive network mount, runs to completion in the background. An unresponsive entry
+    /// also holds on to its worker, and so the remaining entries are shared between fewer
+    /// workers.
+    ///
+    /// This method is only available with the `parallel` feature.
+    ///
+    /// ```rust
+    /// use search_path::SearchPath;
+    /// use std::path::Path;
+    ///
+    /// let search_path = SearchPath::new_or_default("PATH");
+    /// let shell = search_path.find_parallel(Path::new("sh"));
+    /// ```
+    ///
+    pub fn find_parallel(&self, file_name: &Path) -> Option<PathBuf> {
+        self.find_parallel_of_kind(file_name, EntryKind::Any)
+    }
+
+    ///
+    /// Return the first file system entity of `kind` found in the search path, or `None`,
+    /// probing every entry concurrently as described for
+    /// [`find_parallel`](struct.SearchPath.html#method.find_parallel).
+    ///
+    /// This method is only available with the `parallel` feature.
+    ///
+    pub fn find_parallel_of_kind(&self, file_name: &Path, kind: EntryKind) -> Option<PathBuf> {
+        let (state, receiver) = self.spawn_probes(file_name, kind, true);
+        let mut results: Vec<Option<Vec<PathBuf>>> = vec![None; self.entries.len()];
+        let mut next = 0;
+        let found = loop {
+            match results.get(next) {
+                None => break None,
+                Some(Some(paths)) if paths.is_empty() => next += 1,
+                Some(Some(paths)) => break paths.first().cloned(),
+                Some(None) => receive(&receiver, &mut results),
+            }
+        };
+        // Any probe still running is no longer needed.
+        state.finished.store(true, Ordering::Relaxed);
+        found
+    }
+
+    ///
+    /// Return all the file system entities, either file or directory, found in the search path,
+    /// as [`find_all`](struct.SearchPath.html#method.find_all) but probing every entry in the
+    /// search path concurrently as described for
+    /// [`find_parallel`](struct.SearchPath.html#method.find_parallel). The results are in search
+    /// path order.
+    ///
+    /// This method is only available with the `parallel` feature.
+    ///
+    pub fn find_all_parallel(&self, file_name: &Path) -> Vec<PathBuf> {
+        self.find_all_parallel_of_kind(file_name, EntryKind::Any)
+    }
+
+    ///
+    /// Return all the file system entities of `kind` found in the search path, probing every entry
+    /// concurrently as described for
+    /// [`find_all_parallel`](struct.SearchPath.html#method.find_all_parallel).
+    ///
+    /// This method is only available with the `parallel` feature.
+    ///
+    pub fn find_all_parallel_of_kind(&self, file_name: &Path, kind: EntryKind) -> Vec<PathBuf> {
+        let (_, receiver) = self.spawn_probes(file_name, kind, false);
+        let mut results: Vec<Option<Vec<PathBuf>>> = vec![None; self.entries.len()];
+        while results.iter().any(Option::is_none) {
+            receive(&receiver, &mut results);
+        }
+        results.into_iter().flatten().flatten().collect()
+    }
+
+    fn spawn_probes(
+        &self,
+        file_name: &Path,
+        kind: EntryKind,
+        first_only: bool,
+    ) -> (Arc<State>, Receiver<(usize, Vec<PathBuf>)>) {
+        let state = Arc::new(State {
+            next_entry: AtomicUsize::new(0),
+            earliest_match: AtomicUsize::new(usize::MAX),
+            finished: AtomicBool::new(false),
+        });
+        let (sender, receiver) = mpsc::channel();
+        let probe = Probe {
+            entries: Arc::new(self.entries.clone()),
+            index: self.index.clone(),
+            names: Arc::new(self.extensions.candidates(file_name)),
+            kind,
+            first_only,
+            state: state.clone(),
+            sender,
+        };
+        let workers = thread::available_parallelism()
+            .map(NonZeroUsize::get)
+            .unwrap_or(1)
+            .min(self.entries.len());
+        for worker in 0..workers {
+            let probe = probe.clone();
+            // If a thread cannot be created the remaining entries are probed on this thread.
+            let fallback = probe.clone();
+            if thread::Builder::new()
+                .name(format!("search_path-probe-{}", worker))
+                .spawn(move || probe.run())
+                .is_err()
+            {
+                fallback.run();
+                break;
+            }
+        }
+        (state, receiver)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Probe {
+    fn run(self) {
+        loop {
+            let position = self.state.next_entry.fetch_add(1, Ordering::Relaxed);
+            let entry = match self.entries.get(position) {
+                Some(entry) => entry,
+                None => return,
+            };
+            let found = self.probe(position, entry);
+            // The receiver is gone if the lookup has already returned.
+            if self.sender.send((position, found)).is_err() {
+                return;
+            }
+        }
+    }
+
+    fn probe(&self, position: usize, entry: &Entry) -> Vec<PathBuf> {
+        let mut found = Vec::new();
+        'directories: for dir in
+            entry.directories(&OsFileSystem, self.index.as_deref(), Some(&self.names))
+        {
+            for name in self.names.iter() {
+                if self.is_cancelled(position) {
+                    break 'directories;
+                }
+                let path = dir.join(name);
+                if crate::is_match_path(&OsFileSystem, &self.kind, &path) {
+                    found.push(path);
+                    if self.first_only {
+                        let _ = self
+                            .state
+                            .earliest_match
+                            .fetch_min(position, Ordering::Relaxed);
+                        break 'directories;
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    fn is_cancelled(&self, position: usize) -> bool {
+        self.state.finished.load(Ordering::Relaxed)
+            || self.state.earliest_match.load(Ordering::Relaxed) < position
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Wait for the next probe to complete and record its result. If every probe has completed, or
+/// failed, any probe without a result is treated as having found nothing.
+///
+fn receive(receiver: &Receiver<(usize, Vec<PathBuf>)>, results: &mut [Option<Vec<PathBuf>>]) {
+    match receiver.recv() {
+        Ok((position, paths)) => results[position] = Some(paths),
+        Err(_) => {
+            for result in results.iter_mut().filter(|result| result.is_none()) {
+                *result = Some(Vec::new());
+            }
+        }
+    }
+}
@@ -0,0 +1,257 @@
+/*!
+Provides the options that control how a search path string is split into individual paths, and
+how a list of paths is joined back into a string.
+*/
+
+use crate::Entry;
+use std::ffi::{OsStr, OsString};
+use std::fmt::{Display, Formatter};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Determines the syntax of a search path string, independent of the platform this crate was
+/// compiled for.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PathStyle {
+    /// Paths are separated by the `':'` character.
+    Unix,
+    /// Paths are separated by the `';'` character, as parsed by `cmd.exe`. A path may be enclosed,
+    /// in whole or in part, in double quotes in which case it may contain the `';'` character;
+    /// the quotes themselves are removed. As the `':'` character is not a separator, drive
+    /// letters such as `C:\bin` are preserved.
+    Windows,
+}
+
+///
+/// Determines how empty components in a search path string, such as those produced by a leading
+/// or trailing separator or two adjacent separators, are handled.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum EmptyEntries {
+    /// Follow POSIX semantics, an empty component denotes the current directory, `"."`. Components
+    /// that only contain whitespace are kept as-is.
+    CurrentDirectory,
+    /// Drop any component that is empty, or only contains whitespace. This is the behavior of
+    /// the `From<&str>` implementation for `SearchPath`.
+    #[default]
+    Ignore,
+    /// Keep every component verbatim, including empty ones.
+    Preserve,
+}
+
+///
+/// The set of options used to parse a search path string, see
+/// [`SearchPath::parse`](struct.SearchPath.html#method.parse). The default value of this type
+/// reproduces the behavior of the `From<&str>` implementation for `SearchPath`.
+///
+/// When kpathsea syntax is enabled, as used by TeX, a trailing `//` on a component marks the
+/// entry as recursive and a leading `!!` marks it as index-only, see [`Entry`](struct.Entry.html).
+///
+/// ```rust
+/// use search_path::{EmptyEntries, ParseOptions};
+///
+/// let options = ParseOptions::default().with_empty_entries(EmptyEntries::CurrentDirectory);
+/// assert_eq!(options, ParseOptions::posix());
+/// ```
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    style: PathStyle,
+    empty_entries: EmptyEntries,
+    kpathsea: bool,
+}
+
+///
+/// A helper type that implements `Display` for a search path using a specific `PathStyle`, see
+/// [`SearchPath::display_with_style`](struct.SearchPath.html#method.display_with_style).
+///
+#[derive(Debug)]
+pub struct StyledDisplay<'a> {
+    entries: &'a [Entry],
+    style: PathStyle,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Default for PathStyle {
+    fn default() -> Self {
+        Self::native()
+    }
+}
+
+impl PathStyle {
+    ///
+    /// Return the style used by the platform this crate was compiled for.
+    ///
+    #[cfg(target_family = "windows")]
+    pub fn native() -> Self {
+        Self::Windows
+    }
+
+    ///
+    /// Return the style used by the platform this crate was compiled for.
+    ///
+    #[cfg(not(target_family = "windows"))]
+    pub fn native() -> Self {
+        Self::Unix
+    }
+
+    ///
+    /// Return the character used to separate paths in this style.
+    ///
+    pub fn separator(&self) -> char {
+        match self {
+            Self::Unix => ':',
+            Self::Windows => ';',
+        }
+    }
+
+    fn split(&self, s: &OsStr) -> Vec<OsString> {
+        let bytes = s.as_encoded_bytes();
+        match self {
+            Self::Unix => bytes
+                .split(|b| *b == b':')
+                .map(|component| crate::encoded_os_str(component).to_os_string())
+                .collect(),
+            Self::Windows => {
+                let mut components = Vec::new();
+                let mut current = OsString::new();
+                let mut in_quotes = false;
+                let mut start = 0;
+                for (i, b) in bytes.iter().enumerate() {
+                    match b {
+                        b'"' => in_quotes = !in_quotes,
+                        b';' if !in_quotes => {}
+                        _ => continue,
+                    }
+                    current.push(crate::encoded_os_str(&bytes[start..i]));
+                    start = i + 1;
+                    if *b == b';' {
+                        components.push(std::mem::take(&mut current));
+                    }
+                }
+                current.push(crate::encoded_os_str(&bytes[start..]));
+                components.push(current);
+                components
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl ParseOptions {
+    ///
+    /// Return options that parse search path strings the way a POSIX shell parses `PATH`.
+    ///
+    pub fn posix() -> Self {
+        Self::default()
+            .with_style(PathStyle::Unix)
+            .with_empty_entries(EmptyEntries::CurrentDirectory)
+    }
+
+    ///
+    /// Return a copy of these options with the path style set to `style`.
+    ///
+    pub fn with_style(self, style: PathStyle) -> Self {
+        Self { style, ..self }
+    }
+
+    ///
+    /// Return the path style in these options.
+    ///
+    pub fn style(&self) -> PathStyle {
+        self.style
+    }
+
+    ///
+    /// Return a copy of these options with the handling of empty components set to `empty_entries`.
+    ///
+    pub fn with_empty_entries(self, empty_entries: EmptyEntries) -> Self {
+        Self {
+            empty_entries,
+            ..self
+        }
+    }
+
+    ///
+    /// Return the handling of empty components in these options.
+    ///
+    pub fn empty_entries(&self) -> EmptyEntries {
+        self.empty_entries
+    }
+
+    ///
+    /// Return a copy of these options with the recognition of kpathsea syntax, trailing `//` and
+    /// leading `!!` markers, set to `kpathsea`.
+    ///
+    /// ```rust
+    /// use search_path::{ParseOptions, PathStyle, SearchPath};
+    ///
+    /// let options = ParseOptions::default()
+    ///     .with_style(PathStyle::Unix)
+    ///     .with_kpathsea(true);
+    /// let search_path = SearchPath::parse("/usr/share/texmf//:./styles", &options);
+    /// assert!(search_path.entries().next().unwrap().is_recursive());
+    /// ```
+    ///
+    pub fn with_kpathsea(self, kpathsea: bool) -> Self {
+        Self { kpathsea, ..self }
+    }
+
+    ///
+    /// Return `true` if kpathsea syntax is recognized by these options, else `false`.
+    ///
+    pub fn is_kpathsea(&self) -> bool {
+        self.kpathsea
+    }
+
+    pub(crate) fn split(&self, s: &OsStr) -> Vec<Entry> {
+        self.style
+            .split(s)
+            .into_iter()
+            .filter_map(|p| match self.empty_entries {
+                EmptyEntries::CurrentDirectory if p.is_empty() => {
+                    Some(Entry::new(crate::CURRENT_DIR_PATH))
+                }
+                EmptyEntries::Ignore if matches!(p.to_str(), Some(p) if p.trim().is_empty()) => {
+                    None
+                }
+                _ if self.kpathsea => Some(Entry::parse_kpathsea(&p)),
+                _ => Some(Entry::new(p)),
+            })
+            .collect()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for StyledDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let separator = self.style.separator();
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                write!(f, "{}", separator)?;
+            }
+            let path = entry.to_string();
+            if self.style == PathStyle::Windows && path.contains(separator) {
+                write!(f, "\"{}\"", path)?;
+            } else {
+                write!(f, "{}", path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> StyledDisplay<'a> {
+    pub(crate) fn new(entries: &'a [Entry], style: PathStyle) -> Self {
+        Self { entries, style }
+    }
+}
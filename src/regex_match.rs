@@ -0,0 +1,101 @@
+/*!
+Provides the result type for lookups that match directory entries against a regular expression,
+see [`SearchPath::find_regex`](../struct.SearchPath.html#method.find_regex).
+*/
+
+use crate::FileSystem;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A directory entry whose name matched a regular expression, along with the text of each capture
+/// group in the match.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegexMatch {
+    path: PathBuf,
+    captures: Vec<Option<String>>,
+    named: HashMap<String, String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl RegexMatch {
+    pub(crate) fn find_in<F: FileSystem + ?Sized>(
+        file_system: &F,
+        regex: &Regex,
+        dir: &Path,
+    ) -> Vec<Self> {
+        crate::sorted_entries(file_system, dir)
+            .into_iter()
+            .filter_map(|(name, path)| {
+                regex.captures(&name).map(|captures| Self {
+                    path,
+                    captures: captures
+                        .iter()
+                        .map(|c| c.map(|c| c.as_str().to_string()))
+                        .collect(),
+                    named: regex
+                        .capture_names()
+                        .flatten()
+                        .filter_map(|group| {
+                            captures
+                                .name(group)
+                                .map(|c| (group.to_string(), c.as_str().to_string()))
+                        })
+                        .collect(),
+                })
+            })
+            .collect()
+    }
+
+    ///
+    /// Return the path of the matching entry.
+    ///
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    ///
+    /// Return the text of the capture group at `index`, or `None` if the group did not take part
+    /// in the match. Index `0` is always the text matched by the entire expression.
+    ///
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.captures.get(index).and_then(|c| c.as_deref())
+    }
+
+    ///
+    /// Return the text of the capture group named `name`, or `None` if there is no such group or
+    /// it did not take part in the match.
+    ///
+    pub fn name(&self, name: &str) -> Option<&str> {
+        self.named.get(name).map(String::as_str)
+    }
+
+    ///
+    /// Return the number of capture groups, including the implicit group `0`.
+    ///
+    pub fn len(&self) -> usize {
+        self.captures.len()
+    }
+
+    ///
+    /// Return `true` if there are no capture groups, which is never the case for a match.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.captures.is_empty()
+    }
+}
+
+impl From<RegexMatch> for PathBuf {
+    fn from(m: RegexMatch) -> Self {
+        m.path
+    }
+}
@@ -0,0 +1,234 @@
+/*!
+Provides a record of each candidate path considered by a lookup, and why it was accepted or
+rejected, see [`SearchPath::explain`](../struct.SearchPath.html#method.explain).
+*/
+
+use crate::{EntryKind, FileSystem, FileType, Matcher};
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The outcome of checking a single candidate path during a lookup.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The candidate matched, and is the result of the lookup.
+    Found,
+    /// The candidate matched, but an earlier candidate in the search path was already found.
+    Shadowed,
+    /// No file system entry exists at the candidate path.
+    NotFound,
+    /// The candidate is a directory, but a file was required.
+    IsDirectory,
+    /// The candidate exists but is not a directory, and a directory was required.
+    NotDirectory,
+    /// The candidate exists but is neither a file nor a directory, and a file was required.
+    NotFile,
+    /// The candidate is a file, but the current user is not permitted to execute it.
+    NotExecutable,
+    /// The candidate exists but is not of the kind required, for example not a symbolic link.
+    WrongKind,
+    /// The candidate could not be checked as access to it, or a parent directory, was denied.
+    PermissionDenied,
+    /// The candidate is a symbolic link whose target does not exist.
+    BrokenSymlink,
+    /// The candidate could not be checked due to some other I/O error.
+    Error(io::ErrorKind),
+}
+
+///
+/// A single candidate path considered by a lookup, along with the outcome of checking it.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    path: PathBuf,
+    outcome: Outcome,
+}
+
+///
+/// The record of a lookup, listing every candidate path considered in search order. The `Display`
+/// implementation for this type produces a multi-line summary suitable for logs and error
+/// messages.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    file_name: PathBuf,
+    kind: EntryKind,
+    candidates: Vec<Candidate>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Found => write!(f, "found"),
+            Outcome::Shadowed => write!(f, "shadowed by an earlier match"),
+            Outcome::NotFound => write!(f, "not found"),
+            Outcome::IsDirectory => write!(f, "is a directory"),
+            Outcome::NotDirectory => write!(f, "is not a directory"),
+            Outcome::NotFile => write!(f, "is not a regular file"),
+            Outcome::NotExecutable => write!(f, "is not executable"),
+            Outcome::WrongKind => write!(f, "is not of the kind required"),
+            Outcome::PermissionDenied => write!(f, "permission denied"),
+            Outcome::BrokenSymlink => write!(f, "is a broken symbolic link"),
+            Outcome::Error(kind) => write!(f, "I/O error: {:?}", kind),
+        }
+    }
+}
+
+impl Outcome {
+    ///
+    /// Return `true` if the candidate matched the lookup, whether or not it was shadowed.
+    ///
+    pub fn is_match(&self) -> bool {
+        matches!(self, Outcome::Found | Outcome::Shadowed)
+    }
+
+    pub(crate) fn check<F: FileSystem + ?Sized>(
+        file_system: &F,
+        path: &Path,
+        kind: EntryKind,
+    ) -> Self {
+        let metadata = match file_system.metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                // Only a symbolic link can exist while its target does not.
+                return match file_system.symlink_metadata(path) {
+                    Ok(metadata) if metadata.file_type() == FileType::Symlink => {
+                        Outcome::BrokenSymlink
+                    }
+                    _ => Self::from_io_error(&e),
+                };
+            }
+            Err(e) => return Self::from_io_error(&e),
+        };
+        match kind {
+            _ if kind.is_match(path, &metadata) => Outcome::Found,
+            EntryKind::Directory => Outcome::NotDirectory,
+            EntryKind::File | EntryKind::Executable if metadata.is_dir() => Outcome::IsDirectory,
+            EntryKind::File | EntryKind::Executable if !metadata.is_file() => Outcome::NotFile,
+            EntryKind::Executable => Outcome::NotExecutable,
+            _ => Outcome::WrongKind,
+        }
+    }
+
+    fn from_io_error(e: &io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::NotFound | io::ErrorKind::NotADirectory => Outcome::NotFound,
+            io::ErrorKind::PermissionDenied => Outcome::PermissionDenied,
+            kind => Outcome::Error(kind),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Candidate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.outcome)
+    }
+}
+
+impl Candidate {
+    ///
+    /// Return the candidate path that was checked.
+    ///
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    ///
+    /// Return the outcome of checking this candidate.
+    ///
+    pub fn outcome(&self) -> Outcome {
+        self.outcome
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "search for {} `{}`: {}",
+            self.kind,
+            self.file_name.display(),
+            match self.found() {
+                Some(path) => format!("found {}", path.display()),
+                None => "not found".to_string(),
+            }
+        )?;
+        for candidate in &self.candidates {
+            writeln!(f, "  {}", candidate)?;
+        }
+        Ok(())
+    }
+}
+
+impl Trace {
+    pub(crate) fn new<F: FileSystem + ?Sized>(
+        file_system: &F,
+        file_name: &Path,
+        kind: EntryKind,
+        paths: impl Iterator<Item = PathBuf>,
+    ) -> Self {
+        let mut found = false;
+        let candidates = paths
+            .map(|path| {
+                let mut outcome = Outcome::check(file_system, &path, kind);
+                if outcome == Outcome::Found {
+                    if found {
+                        outcome = Outcome::Shadowed;
+                    }
+                    found = true;
+                }
+                Candidate { path, outcome }
+            })
+            .collect();
+        Self {
+            file_name: file_name.to_path_buf(),
+            kind,
+            candidates,
+        }
+    }
+
+    ///
+    /// Return the name that was searched for.
+    ///
+    pub fn file_name(&self) -> &PathBuf {
+        &self.file_name
+    }
+
+    ///
+    /// Return the kind of file system entry that was searched for.
+    ///
+    pub fn kind(&self) -> EntryKind {
+        self.kind
+    }
+
+    ///
+    /// Return the path that the lookup would return, or `None`.
+    ///
+    pub fn found(&self) -> Option<&PathBuf> {
+        self.candidates
+            .iter()
+            .find(|candidate| candidate.outcome == Outcome::Found)
+            .map(Candidate::path)
+    }
+
+    ///
+    /// Return an iterator over all the candidates considered, in search order.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = &Candidate> {
+        self.candidates.iter()
+    }
+}
@@ -0,0 +1,174 @@
+/*!
+Provides the options for, and implementation of, the breadth-first directory walk used by the
+recursive lookups, see [`SearchPath::find_recursive`](../struct.SearchPath.html#method.find_recursive).
+*/
+
+use crate::FileSystem;
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The set of options used by the recursive lookups. By default the walk has no depth limit,
+/// follows symbolic links to directories, and includes hidden directories, those whose name
+/// starts with `'.'`.
+///
+/// ```rust
+/// use search_path::WalkOptions;
+///
+/// let options = WalkOptions::default().with_max_depth(3).with_skip_hidden(true);
+/// ```
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WalkOptions {
+    max_depth: Option<usize>,
+    skip_hidden: bool,
+    follow_symlinks: bool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A lazy, breadth-first, iterator over a directory and its sub-directories. Sub-directories are
+/// visited in sorted order and any directory already visited, for example via a symbolic link,
+/// is skipped; directories are identified by their canonical path within the file system.
+///
+#[derive(Debug)]
+pub(crate) struct Walk<'a, F: FileSystem + ?Sized> {
+    file_system: &'a F,
+    options: WalkOptions,
+    queue: VecDeque<(PathBuf, usize)>,
+    visited: HashSet<PathBuf>,
+}
+
+const HIDDEN_PREFIX_CHAR: char = '.';
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            skip_hidden: false,
+            follow_symlinks: true,
+        }
+    }
+}
+
+impl WalkOptions {
+    ///
+    /// Return a copy of these options with the maximum depth set to `max_depth`. A depth of `0`
+    /// only considers the search path entry itself, `1` also considers its immediate
+    /// sub-directories, and so on.
+    ///
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        Self {
+            max_depth: Some(max_depth),
+            ..self
+        }
+    }
+
+    ///
+    /// Return the maximum depth of the walk, or `None` if there is no limit.
+    ///
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    ///
+    /// Return a copy of these options with the skipping of hidden directories set to
+    /// `skip_hidden`.
+    ///
+    pub fn with_skip_hidden(self, skip_hidden: bool) -> Self {
+        Self {
+            skip_hidden,
+            ..self
+        }
+    }
+
+    ///
+    /// Return `true` if directories whose name starts with `'.'` are skipped, else `false`.
+    ///
+    pub fn skip_hidden(&self) -> bool {
+        self.skip_hidden
+    }
+
+    ///
+    /// Return a copy of these options with the following of symbolic links to directories set to
+    /// `follow_symlinks`.
+    ///
+    pub fn with_follow_symlinks(self, follow_symlinks: bool) -> Self {
+        Self {
+            follow_symlinks,
+            ..self
+        }
+    }
+
+    ///
+    /// Return `true` if symbolic links to directories are followed, else `false`.
+    ///
+    pub fn follow_symlinks(&self) -> bool {
+        self.follow_symlinks
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<F: FileSystem + ?Sized> Iterator for Walk<'_, F> {
+    type Item = PathBuf;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (dir, depth) = self.queue.pop_front()?;
+        if self
+            .options
+            .max_depth
+            .map(|max| depth < max)
+            .unwrap_or(true)
+        {
+            for (name, path) in crate::sorted_entries(self.file_system, &dir) {
+                if self.options.skip_hidden && name.starts_with(HIDDEN_PREFIX_CHAR) {
+                    continue;
+                }
+                if self.is_new_dir(&path, self.options.follow_symlinks) {
+                    self.queue.push_back((path, depth + 1));
+                }
+            }
+        }
+        Some(dir)
+    }
+}
+
+impl<'a, F: FileSystem + ?Sized> Walk<'a, F> {
+    pub(crate) fn new(file_system: &'a F, root: &Path, options: &WalkOptions) -> Self {
+        let mut walk = Self {
+            file_system,
+            options: options.clone(),
+            queue: Default::default(),
+            visited: Default::default(),
+        };
+        // The search path entry itself is always followed, even if it is a symbolic link.
+        if walk.is_new_dir(root, true) {
+            walk.queue.push_back((root.to_path_buf(), 0));
+        }
+        walk
+    }
+
+    fn is_new_dir(&mut self, path: &Path, follow_symlinks: bool) -> bool {
+        match self.file_system.metadata(path) {
+            Ok(metadata) if metadata.is_dir() && (follow_symlinks || !metadata.is_symlink()) => {
+                match self.file_system.canonicalize(path) {
+                    Ok(canonical) => self.visited.insert(canonical),
+                    Err(_) => false,
+                }
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,456 @@
+/*!
+Provides a watcher, using Linux inotify, that keeps a cached search path fresh and reports changes
+to the entries that lookups would return, see [`CachedSearchPath::watch`](../struct.CachedSearchPath.html#method.watch).
+*/
+
+use crate::{CachedSearchPath, Entry, Error, FileSystem, OsFileSystem};
+use std::collections::HashMap;
+use std::ffi::{CString, OsStr, OsString};
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A change to the file system entries in a watched search path.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// An entry appeared in one of the directories of the search path.
+    Appeared {
+        /// The path of the new entry.
+        path: PathBuf,
+        /// `true` if the new entry is now the first match for its name, else `false`.
+        first_match: bool,
+        /// If the new entry is now the first match for its name, the entry that was the first
+        /// match before it appeared, if any; always `None` if `first_match` is `false`.
+        shadowing: Option<PathBuf>,
+    },
+    /// An entry was removed from one of the directories of the search path.
+    Disappeared {
+        /// The path of the removed entry.
+        path: PathBuf,
+        /// The first match for the same name now that the entry has been removed, if any.
+        resolves_to: Option<PathBuf>,
+    },
+    /// The kernel's event queue overflowed and some events were lost; all cached listings have
+    /// been invalidated.
+    Overflow,
+}
+
+///
+/// A guard returned when watching a search path, dropping the guard stops watching and waits for
+/// the background thread to finish.
+///
+#[derive(Debug)]
+pub struct WatchGuard {
+    stop: File,
+    thread: Option<JoinHandle<()>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+struct Watcher<F> {
+    cached: Arc<CachedSearchPath>,
+    inotify: File,
+    stop: RawFd,
+    directories: HashMap<i32, PathBuf>,
+    parents: HashMap<i32, PathBuf>,
+    callback: F,
+}
+
+const WATCH_MASK: u32 = libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO
+    | libc::IN_DELETE_SELF
+    | libc::IN_MOVE_SELF
+    | libc::IN_ONLYDIR;
+
+const EVENT_HEADER_LEN: usize = 16;
+
+const EVENT_BUFFER_LEN: usize = 64 * 1024;
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for WatchEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatchEvent::Appeared {
+                path,
+                first_match,
+                shadowing,
+            } => {
+                write!(f, "{}", describe(path, "appeared in"))?;
+                match (first_match, shadowing) {
+                    (true, Some(shadowing)) => {
+                        write!(f, ", now shadowing `{}`", shadowing.display())
+                    }
+                    (true, None) => Ok(()),
+                    (false, _) => write!(f, ", shadowed by an earlier entry"),
+                }
+            }
+            WatchEvent::Disappeared { path, resolves_to } => {
+                write!(f, "{}", describe(path, "disappeared from"))?;
+                match resolves_to {
+                    Some(resolves_to) => {
+                        write!(f, ", now resolving to `{}`", resolves_to.display())
+                    }
+                    None => write!(f, ", no longer found"),
+                }
+            }
+            WatchEvent::Overflow => write!(f, "events were lost, all cached listings invalidated"),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Drop for WatchGuard {
+    fn drop(&mut self) {
+        // An eventfd counter is written as a native-endian `u64`, any non-zero value wakes the
+        // watcher thread.
+        let _ = self.stop.write_all(&1u64.to_ne_bytes());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl CachedSearchPath {
+    ///
+    /// Watch every directory in the search path, including those below recursive entries, using
+    /// Linux inotify. When an entry appears in, or disappears from, a directory the cached listing
+    /// of that directory is invalidated and `callback` is called, on a background thread, with a
+    /// description of the change. Watching stops when the returned guard is dropped.
+    ///
+    /// Directories created below a recursive entry are watched as they appear. The parent of
+    /// each entry in the search path is also watched, so that an entry which does not exist when
+    /// watching starts, or which is removed and created again, is watched once it is created and
+    /// the entries already within it are reported as having appeared. This is only possible while
+    /// the parent directory itself exists; if it is removed the entry is no longer watched.
+    ///
+    /// This method is only available on Linux.
+    ///
+    /// ```rust,no_run
+    /// use search_path::{CachedSearchPath, SearchPath};
+    /// use std::sync::Arc;
+    ///
+    /// let cached = Arc::new(CachedSearchPath::from(SearchPath::new_or_default("PATH")));
+    /// let _guard = cached.watch(|event| println!("{}", event)).unwrap();
+    /// ```
+    ///
+    pub fn watch<F>(self: &Arc<Self>, callback: F) -> Result<WatchGuard, Error>
+    where
+        F: FnMut(WatchEvent) + Send + 'static,
+    {
+        let inotify = inotify_init().map_err(|e| Error::io(PathBuf::new(), e))?;
+        let stop = eventfd().map_err(|e| Error::io(PathBuf::new(), e))?;
+        let mut watcher = Watcher {
+            cached: self.clone(),
+            inotify: File::from(inotify),
+            stop: stop.as_raw_fd(),
+            directories: Default::default(),
+            parents: Default::default(),
+            callback,
+        };
+        for directory in self.directories() {
+            watcher.add_watch(&directory)?;
+        }
+        for entry in self.search_path().entries() {
+            watcher.add_parent_watch(entry)?;
+        }
+        Ok(WatchGuard {
+            stop: File::from(stop),
+            thread: Some(thread::spawn(move || watcher.run())),
+        })
+    }
+
+    ///
+    /// Watch every directory in the search path, as [`watch`](struct.CachedSearchPath.html#method.watch),
+    /// but send each event to the returned channel rather than calling a callback.
+    ///
+    /// This method is only available on Linux.
+    ///
+    pub fn subscribe(self: &Arc<Self>) -> Result<(WatchGuard, Receiver<WatchEvent>), Error> {
+        let (sender, receiver) = mpsc::channel();
+        let guard = self.watch(move |event| {
+            let _ = sender.send(event);
+        })?;
+        Ok((guard, receiver))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<F: FnMut(WatchEvent)> Watcher<F> {
+    fn run(mut self) {
+        let mut buffer = vec![0u8; EVENT_BUFFER_LEN];
+        while let Ok(true) = wait(self.inotify.as_raw_fd(), self.stop) {
+            match self.inotify.read(&mut buffer) {
+                Ok(len) => self.handle_events(&buffer[..len]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+    }
+
+    fn handle_events(&mut self, mut buffer: &[u8]) {
+        while buffer.len() >= EVENT_HEADER_LEN {
+            let field = |i: usize| {
+                let mut bytes = [0; 4];
+                bytes.copy_from_slice(&buffer[i * 4..i * 4 + 4]);
+                bytes
+            };
+            let wd = i32::from_ne_bytes(field(0));
+            let mask = u32::from_ne_bytes(field(1));
+            let len = u32::from_ne_bytes(field(3)) as usize;
+            let end = (EVENT_HEADER_LEN + len).min(buffer.len());
+            let name: Vec<u8> = buffer[EVENT_HEADER_LEN..end]
+                .iter()
+                .copied()
+                .take_while(|b| *b != 0)
+                .collect();
+            buffer = &buffer[end..];
+            self.handle_event(wd, mask, OsString::from_vec(name));
+        }
+    }
+
+    fn handle_event(&mut self, wd: i32, mask: u32, name: OsString) {
+        if mask & libc::IN_Q_OVERFLOW != 0 {
+            self.cached.invalidate_all();
+            (self.callback)(WatchEvent::Overflow);
+            return;
+        }
+        let created = mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0;
+        if let Some(parent) = self.parents.get(&wd) {
+            let path = parent.join(&name);
+            if created && mask & libc::IN_ISDIR != 0 && !self.is_watched(&path) {
+                self.entry_created(path);
+            }
+        }
+        if mask & libc::IN_IGNORED != 0 {
+            let _ = self.parents.remove(&wd);
+        }
+        let directory = match self.directories.get(&wd) {
+            Some(directory) => directory.clone(),
+            None => return,
+        };
+        if mask & libc::IN_IGNORED != 0 {
+            let _ = self.directories.remove(&wd);
+        }
+        self.cached.invalidate(&directory);
+        if name.is_empty() {
+            return;
+        }
+        let path = directory.join(&name);
+        if created {
+            if mask & libc::IN_ISDIR != 0 && self.is_below_recursive_entry(&directory) {
+                for directory in self.cached.walk(&path) {
+                    // A failure here only means the new directory is not watched.
+                    let _ = self.add_watch(&directory);
+                }
+            }
+            self.appeared(path);
+        } else if mask & (libc::IN_DELETE | libc::IN_MOVED_FROM) != 0 {
+            let resolves_to = self.cached.find(Path::new(&name));
+            (self.callback)(WatchEvent::Disappeared { path, resolves_to });
+        }
+    }
+
+    ///
+    /// Watch `path`, an entry of the search path that has been created in its parent directory,
+    /// and report everything already within it as having appeared.
+    ///
+    fn entry_created(&mut self, path: PathBuf) {
+        let entry = match self
+            .cached
+            .search_path()
+            .entries()
+            .find(|entry| !entry.is_index_only() && entry.path() == &path)
+        {
+            Some(entry) => entry.clone(),
+            None => return,
+        };
+        self.cached.invalidate(&path);
+        let directories = if entry.is_recursive() {
+            self.cached.walk(&path)
+        } else {
+            vec![path.clone()]
+        };
+        for directory in directories {
+            // A failure here only means the new directory is not watched.
+            let _ = self.add_watch(&directory);
+        }
+        let mut paths = OsFileSystem.read_dir(&path).unwrap_or_default();
+        paths.sort();
+        for path in paths {
+            self.appeared(path);
+        }
+    }
+
+    fn appeared(&mut self, path: PathBuf) {
+        let name = match path.file_name() {
+            Some(name) => name,
+            None => return,
+        };
+        let all = self.cached.find_all(Path::new(name));
+        let first_match = all.first() == Some(&path);
+        let shadowing = if first_match {
+            all.get(1).cloned()
+        } else {
+            None
+        };
+        (self.callback)(WatchEvent::Appeared {
+            path,
+            first_match,
+            shadowing,
+        });
+    }
+
+    fn is_watched(&self, directory: &Path) -> bool {
+        self.directories
+            .values()
+            .any(|watched| watched == directory)
+    }
+
+    fn is_below_recursive_entry(&self, directory: &Path) -> bool {
+        self.cached
+            .search_path()
+            .entries()
+            .any(|entry| entry.is_recursive() && directory.starts_with(entry.path()))
+    }
+
+    fn add_watch(&mut self, directory: &Path) -> Result<(), Error> {
+        match inotify_add_watch(self.inotify.as_raw_fd(), directory) {
+            Ok(wd) => {
+                let _ = self.directories.insert(wd, directory.to_path_buf());
+                Ok(())
+            }
+            Err(e) if matches!(e.raw_os_error(), Some(libc::ENOENT) | Some(libc::ENOTDIR)) => {
+                Ok(())
+            }
+            Err(e) => Err(Error::io(directory, e)),
+        }
+    }
+
+    fn add_parent_watch(&mut self, entry: &Entry) -> Result<(), Error> {
+        if entry.is_index_only() {
+            return Ok(());
+        }
+        let parent = match entry.path().parent() {
+            Some(parent) if parent.as_os_str().is_empty() => Path::new(crate::CURRENT_DIR_PATH),
+            Some(parent) => parent,
+            None => return Ok(()),
+        };
+        // Watching the same directory twice returns the same descriptor with the same mask, so
+        // a parent may also be one of the watched directories.
+        match inotify_add_watch(self.inotify.as_raw_fd(), parent) {
+            Ok(wd) => {
+                let _ = self.parents.insert(wd, parent.to_path_buf());
+                Ok(())
+            }
+            Err(e) if matches!(e.raw_os_error(), Some(libc::ENOENT) | Some(libc::ENOTDIR)) => {
+                Ok(())
+            }
+            Err(e) => Err(Error::io(parent, e)),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn describe(path: &Path, verb: &str) -> String {
+    format!(
+        "`{}` {} `{}`",
+        path.file_name()
+            .unwrap_or_else(|| OsStr::new(""))
+            .to_string_lossy(),
+        verb,
+        path.parent().unwrap_or_else(|| Path::new("")).display()
+    )
+}
+
+#[allow(unsafe_code)]
+fn inotify_init() -> io::Result<OwnedFd> {
+    // SAFETY: this call has no preconditions, and a non-negative result is a new descriptor
+    // owned by no-one else.
+    unsafe {
+        match libc::inotify_init1(libc::IN_CLOEXEC) {
+            -1 => Err(io::Error::last_os_error()),
+            fd => Ok(OwnedFd::from_raw_fd(fd)),
+        }
+    }
+}
+
+#[allow(unsafe_code)]
+fn eventfd() -> io::Result<OwnedFd> {
+    // SAFETY: as for `inotify_init`.
+    unsafe {
+        match libc::eventfd(0, libc::EFD_CLOEXEC) {
+            -1 => Err(io::Error::last_os_error()),
+            fd => Ok(OwnedFd::from_raw_fd(fd)),
+        }
+    }
+}
+
+#[allow(unsafe_code)]
+fn inotify_add_watch(fd: RawFd, path: &Path) -> io::Result<i32> {
+    let path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    // SAFETY: `path` is a valid NUL terminated string for the duration of the call.
+    match unsafe { libc::inotify_add_watch(fd, path.as_ptr(), WATCH_MASK) } {
+        -1 => Err(io::Error::last_os_error()),
+        wd => Ok(wd),
+    }
+}
+
+///
+/// Wait until `fd` is readable, returning `true`, or until `stop` is readable, returning `false`.
+///
+#[allow(unsafe_code)]
+fn wait(fd: RawFd, stop: RawFd) -> io::Result<bool> {
+    let mut fds = [
+        libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        },
+        libc::pollfd {
+            fd: stop,
+            events: libc::POLLIN,
+            revents: 0,
+        },
+    ];
+    loop {
+        // SAFETY: `fds` is a valid array of the length given for the duration of the call.
+        match unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) } {
+            -1 => {
+                let e = io::Error::last_os_error();
+                if e.kind() != io::ErrorKind::Interrupted {
+                    return Err(e);
+                }
+            }
+            _ if fds[1].revents != 0 => return Ok(false),
+            _ if fds[0].revents & libc::POLLIN != 0 => return Ok(true),
+            _ => return Err(io::ErrorKind::BrokenPipe.into()),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "tokio")]
+
+use futures_core::Stream;
+use search_path::{EntryKind, MatchStream, SearchPath};
+use std::future::poll_fn;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+
+const NAMES: &[&str] = &[
+    "a.txt",
+    "b.txt",
+    "c.txt",
+    "d",
+    "x.txt",
+    "d/no-op.txt",
+    "none.txt",
+];
+
+fn make_search_path() -> SearchPath {
+    vec![
+        "tests",
+        "tests/a",
+        "tests/b",
+        "tests/c",
+        "tests/none",
+        "tests/e/f",
+        "tests/e/f/g",
+    ]
+    .into()
+}
+
+async fn next(stream: &mut MatchStream) -> Option<PathBuf> {
+    poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+}
+
+#[tokio::test]
+async fn find_async_matches_find() {
+    let search_path = make_search_path();
+    for name in NAMES {
+        let name = Path::new(name);
+        assert_eq!(search_path.find_async(name).await, search_path.find(name));
+        assert_eq!(
+            search_path.find_all_async(name).await,
+            search_path.find_all(name)
+        );
+        assert_eq!(
+            search_path.find_file_async(name).await,
+            search_path.find_file(name)
+        );
+        assert_eq!(
+            search_path.find_executable_async(name).await,
+            search_path.find_executable(name)
+        );
+    }
+}
+
+#[tokio::test]
+async fn matches_async_in_order() {
+    let search_path = make_search_path();
+    for name in NAMES {
+        let name = Path::new(name);
+        let mut stream = search_path.matches_async(name);
+        let mut found = Vec::new();
+        while let Some(path) = next(&mut stream).await {
+            found.push(path);
+        }
+        assert_eq!(found, search_path.find_all(name));
+    }
+}
+
+#[tokio::test]
+async fn matches_async_of_kind_dropped_early() {
+    let search_path = make_search_path();
+    let mut stream = search_path.matches_async_of_kind(Path::new("a.txt"), EntryKind::File);
+    assert_eq!(next(&mut stream).await, Some(PathBuf::from("tests/a.txt")));
+    drop(stream);
+    assert!(SearchPath::default()
+        .find_all_async(Path::new("a.txt"))
+        .await
+        .is_empty());
+}
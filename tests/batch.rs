@@ -0,0 +1,124 @@
+use search_path::{EntryKind, Extensions, NegativeCache, SearchPath};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const NAMES: &[&str] = &[
+    "a.txt",
+    "b.txt",
+    "c.txt",
+    "d",
+    "x.txt",
+    "d/no-op.txt",
+    "none.txt",
+];
+
+fn make_search_path() -> SearchPath {
+    vec![
+        "tests",
+        "tests/a",
+        "tests/b",
+        "tests/c",
+        "tests/none",
+        "tests/e/f",
+        "tests/e/f/g",
+    ]
+    .into()
+}
+
+#[test]
+fn find_many_matches_find() {
+    let search_path = make_search_path();
+    let expected: HashMap<PathBuf, PathBuf> = NAMES
+        .iter()
+        .filter_map(|name| {
+            search_path
+                .find(Path::new(name))
+                .map(|path| (PathBuf::from(name), path))
+        })
+        .collect();
+    assert_eq!(search_path.find_many(NAMES), expected);
+    assert!(!expected.contains_key(Path::new("none.txt")));
+    assert_eq!(
+        expected.get(Path::new("a.txt")),
+        Some(&PathBuf::from("tests/a.txt"))
+    );
+}
+
+#[test]
+fn find_all_many_matches_find_all() {
+    let search_path = make_search_path();
+    let expected: HashMap<PathBuf, Vec<PathBuf>> = NAMES
+        .iter()
+        .map(|name| (PathBuf::from(name), search_path.find_all(Path::new(name))))
+        .filter(|(_, paths)| !paths.is_empty())
+        .collect();
+    assert_eq!(search_path.find_all_many(NAMES), expected);
+}
+
+#[test]
+fn find_many_of_kind_matches_find_all_of_kind() {
+    let search_path = make_search_path();
+    for kind in [EntryKind::File, EntryKind::Directory] {
+        let expected: HashMap<PathBuf, Vec<PathBuf>> = NAMES
+            .iter()
+            .map(|name| {
+                let paths = search_path.find_all_of_kind(Path::new(name), kind);
+                (PathBuf::from(name), paths)
+            })
+            .filter(|(_, paths)| !paths.is_empty())
+            .collect();
+        assert_eq!(search_path.find_all_many_of_kind(NAMES, kind), expected);
+        assert_eq!(
+            search_path.find_many_of_kind(NAMES, kind).len(),
+            expected.len()
+        );
+    }
+    assert_eq!(
+        search_path.find_many_of_kind(["d"], EntryKind::File),
+        HashMap::new()
+    );
+}
+
+#[test]
+fn find_many_with_extensions() {
+    let mut search_path: SearchPath = vec!["tests/ext/a", "tests/ext/b"].into();
+    search_path.set_extensions(Extensions::from(".sh;.py"));
+    let results = search_path.find_all_many(vec![Path::new("tool"), Path::new("tool")]);
+    assert_eq!(results.len(), 1);
+    assert_eq!(
+        results.get(Path::new("tool")),
+        Some(&search_path.find_all(Path::new("tool")))
+    );
+}
+
+#[test]
+fn find_many_empty() {
+    let search_path = make_search_path();
+    assert!(search_path.find_many(Vec::<PathBuf>::new()).is_empty());
+    assert!(SearchPath::default().find_many(NAMES).is_empty());
+}
+
+#[test]
+fn find_many_ignores_ascii_case_in_listings() {
+    let search_path: SearchPath = vec!["tests/a", "tests/b"].into();
+    let results = search_path.find_many(["B.TXT", "a.txt"]);
+    assert_eq!(
+        results.get(Path::new("B.TXT")),
+        search_path.find(Path::new("B.TXT")).as_ref()
+    );
+    assert_eq!(
+        results.get(Path::new("a.txt")),
+        Some(&PathBuf::from("tests/b/a.txt"))
+    );
+}
+
+#[test]
+fn find_many_uses_negative_cache() {
+    let mut search_path = make_search_path();
+    search_path.set_negative_cache(NegativeCache::default());
+    assert!(search_path.find_many(["none.txt"]).is_empty());
+    assert_eq!(search_path.find(Path::new("none.txt")), None);
+    assert_eq!(search_path.find_many(["none.txt", "a.txt"]).len(), 1);
+    let stats = search_path.negative_cache().unwrap().stats();
+    assert_eq!((stats.hits(), stats.misses()), (2, 1));
+}
@@ -0,0 +1,151 @@
+use search_path::{CacheOptions, CachedSearchPath, ParseOptions, PathStyle, SearchPath};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+mod common;
+use common::make_temp_dir;
+
+fn make_search_path() -> SearchPath {
+    vec![
+        "tests",
+        "tests/a",
+        "tests/b",
+        "tests/c",
+        "tests/e/f",
+        "tests/e/f/g",
+    ]
+    .into()
+}
+
+#[test]
+fn cached_find_matches_uncached() {
+    let search_path = make_search_path();
+    let cached = CachedSearchPath::from(search_path.clone());
+    for name in &["a.txt", "b.txt", "d", "x.txt", "d/no-op.txt", "none.txt"] {
+        let name = Path::new(name);
+        assert_eq!(cached.find(name), search_path.find(name));
+        assert_eq!(cached.find_all(name), search_path.find_all(name));
+        assert_eq!(cached.find_file(name), search_path.find_file(name));
+        assert_eq!(
+            cached.find_directory(name),
+            search_path.find_directory(name)
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn cached_find_executable_matches_uncached() {
+    let search_path: SearchPath = vec![
+        "tests/exec/a",
+        "tests/exec/b",
+        "tests/exec/c",
+        "tests/exec/d",
+        "tests/exec/e",
+    ]
+    .into();
+    let cached = CachedSearchPath::from(search_path.clone());
+    let name = Path::new("tool");
+    assert_eq!(
+        cached.find_executable(name),
+        search_path.find_executable(name)
+    );
+    assert_eq!(
+        cached.find_executable(name),
+        Some(std::path::PathBuf::from("tests/exec/d/tool"))
+    );
+    assert_eq!(cached.find_file(name), search_path.find_file(name));
+}
+
+#[test]
+fn cached_glob_matches_uncached() {
+    let search_path = make_search_path();
+    let cached = CachedSearchPath::from(search_path.clone());
+    for pattern in &["*.txt", "?", "d/*", "**/no-op.txt", ".*", "{a,b}.txt"] {
+        assert_eq!(
+            cached.find_glob(pattern).unwrap(),
+            search_path.find_glob(pattern).unwrap()
+        );
+        assert_eq!(
+            cached.find_all_glob(pattern).unwrap(),
+            search_path.find_all_glob(pattern).unwrap()
+        );
+    }
+}
+
+#[test]
+fn cached_find_recursive_entry() {
+    let search_path = SearchPath::parse(
+        "tests/walk//",
+        &ParseOptions::default()
+            .with_style(PathStyle::Unix)
+            .with_kpathsea(true),
+    );
+    let cached = CachedSearchPath::from(search_path.clone());
+    assert_eq!(
+        cached.find_all(Path::new("target.txt")),
+        search_path.find_all(Path::new("target.txt"))
+    );
+}
+
+#[test]
+fn cached_explicit_invalidation() {
+    let dir = make_temp_dir("cached-invalidate");
+    let cached = CachedSearchPath::new(
+        vec![dir.clone()].into(),
+        CacheOptions::default().with_revalidate(false),
+    );
+    let before = cached.find(Path::new("new.txt"));
+    fs::write(dir.join("new.txt"), "").unwrap();
+    let cached_result = cached.find(Path::new("new.txt"));
+    cached.invalidate(&dir);
+    let invalidated = cached.find(Path::new("new.txt"));
+    fs::remove_file(dir.join("new.txt")).unwrap();
+    let still_cached = cached.find(Path::new("new.txt"));
+    cached.invalidate_all();
+    let after = cached.find(Path::new("new.txt"));
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(before, None);
+    assert_eq!(cached_result, None);
+    assert_eq!(invalidated, Some(dir.join("new.txt")));
+    assert_eq!(still_cached, Some(dir.join("new.txt")));
+    assert_eq!(after, None);
+}
+
+#[test]
+fn cached_ttl_expiry() {
+    let dir = make_temp_dir("cached-ttl");
+    let cached = CachedSearchPath::new(
+        vec![dir.clone()].into(),
+        CacheOptions::default()
+            .with_revalidate(false)
+            .with_ttl(Duration::from_millis(0)),
+    );
+    let before = cached.find(Path::new("new.txt"));
+    fs::write(dir.join("new.txt"), "").unwrap();
+    let after = cached.find(Path::new("new.txt"));
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(before, None);
+    assert_eq!(after, Some(dir.join("new.txt")));
+}
+
+#[cfg(unix)]
+#[test]
+fn cached_revalidation() {
+    use std::time::UNIX_EPOCH;
+    let dir = make_temp_dir("cached-revalidate");
+    let cached = CachedSearchPath::from(SearchPath::from(vec![dir.clone()]));
+    let before = cached.find(Path::new("new.txt"));
+    fs::write(dir.join("new.txt"), "").unwrap();
+    // Set the modification time explicitly, file systems with a coarse granularity may not
+    // otherwise record a change.
+    fs::File::open(&dir)
+        .unwrap()
+        .set_modified(UNIX_EPOCH + Duration::from_secs(1_000_000))
+        .unwrap();
+    let after = cached.find(Path::new("new.txt"));
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(before, None);
+    assert_eq!(after, Some(dir.join("new.txt")));
+}
@@ -0,0 +1,8 @@
+use std::fs;
+use std::path::PathBuf;
+
+pub fn make_temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("search_path-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -1,4 +1,4 @@
-use search_path::SearchPath;
+use search_path::{EmptyEntries, Error, ParseOptions, SearchPath};
 use std::path::{Path, PathBuf};
 
 #[cfg(target_family = "windows")]
@@ -89,3 +89,180 @@ fn from_pathbuf() {
     assert_eq!(search_path.len(), 1);
     assert!(search_path.contains(&PathBuf::from(".")));
 }
+
+#[test]
+fn test_no_env_var_error() {
+    let search_path = SearchPath::new("UNLIKELY_THIS_VAR_EXISTS");
+    match search_path {
+        Err(Error::NotPresent { name }) => assert_eq!(name, "UNLIKELY_THIS_VAR_EXISTS"),
+        _ => panic!("expected Error::NotPresent"),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_not_unicode_env_var_error() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    std::env::set_var("SEARCH_PATH_NOT_UNICODE", OsStr::from_bytes(b"a:\xFF"));
+    let search_path = SearchPath::new("SEARCH_PATH_NOT_UNICODE");
+    assert!(matches!(search_path, Err(Error::NotUnicode { .. })));
+}
+
+#[test]
+fn from_os_str() {
+    use std::ffi::OsStr;
+    let search_path: SearchPath = OsStr::new(NOT_SO_SIMPLE_PATH).into();
+    assert_correct(&search_path);
+}
+
+#[test]
+fn from_os_string() {
+    use std::ffi::OsString;
+    let search_path: SearchPath = OsString::from(SIMPLE_PATH).into();
+    assert_correct(&search_path);
+}
+
+#[test]
+fn test_no_env_var_os() {
+    let search_path = SearchPath::new_os("UNLIKELY_THIS_VAR_EXISTS");
+    assert!(matches!(search_path, Err(Error::NotPresent { .. })));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_not_unicode_env_var_os() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    std::env::set_var(
+        "SEARCH_PATH_NOT_UNICODE_OS",
+        OsStr::from_bytes(b"a:b\xFF:c"),
+    );
+    let search_path = SearchPath::new_os("SEARCH_PATH_NOT_UNICODE_OS").unwrap();
+    assert_eq!(search_path.len(), 3);
+    assert!(search_path.contains(&PathBuf::from(OsStr::from_bytes(b"b\xFF"))));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_not_unicode_env_var_with_options() {
+    use search_path::{EmptyEntries, ParseOptions};
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    std::env::set_var(
+        "SEARCH_PATH_NOT_UNICODE_OPTIONS",
+        OsStr::from_bytes(b"a::b\xFF//"),
+    );
+    let options = ParseOptions::posix().with_kpathsea(true);
+    assert_eq!(options.empty_entries(), EmptyEntries::CurrentDirectory);
+    let search_path =
+        SearchPath::new_with_options("SEARCH_PATH_NOT_UNICODE_OPTIONS", &options).unwrap();
+    let entries: Vec<_> = search_path.entries().collect();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[1].path(), &PathBuf::from("."));
+    assert_eq!(
+        entries[2].path(),
+        &PathBuf::from(OsStr::from_bytes(b"b\xFF"))
+    );
+    assert!(entries[2].is_recursive());
+}
+
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn to_os_string() {
+    use std::ffi::OsString;
+    let search_path: SearchPath = SIMPLE_PATH.into();
+    assert_eq!(
+        search_path.to_os_string().unwrap(),
+        OsString::from(SIMPLE_PATH)
+    );
+    assert_eq!(
+        search_path.to_env_value().unwrap(),
+        OsString::from(SIMPLE_PATH)
+    );
+}
+
+#[test]
+fn to_os_string_separator_error() {
+    let search_path: SearchPath = vec![PathBuf::from("."), PathBuf::from(SIMPLE_PATH)].into();
+    match search_path.to_os_string() {
+        Err(Error::InvalidEntry { entry, .. }) => assert_eq!(entry, PathBuf::from(SIMPLE_PATH)),
+        _ => panic!("expected Error::InvalidEntry"),
+    }
+}
+
+#[test]
+fn to_os_string_nul_error() {
+    let search_path: SearchPath = vec![PathBuf::from("a\0b")].into();
+    assert!(matches!(
+        search_path.to_os_string(),
+        Err(Error::InvalidEntry { .. })
+    ));
+}
+
+#[cfg(unix)]
+#[test]
+fn to_os_string_not_unicode() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    let value = OsStr::from_bytes(b"a:b\xFF:c");
+    let search_path: SearchPath = value.into();
+    assert_eq!(search_path.to_os_string().unwrap(), value);
+}
+
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(not(target_family = "windows"))]
+const EMPTY_COMPONENTS_PATH: &str = ":a:: :b:";
+#[cfg(target_family = "windows")]
+const EMPTY_COMPONENTS_PATH: &str = ";a;; ;b;";
+
+#[test]
+fn parse_empty_entries_ignore() {
+    let search_path = SearchPath::parse(EMPTY_COMPONENTS_PATH, &ParseOptions::default());
+    assert_eq!(
+        Vec::<PathBuf>::from(search_path),
+        vec![PathBuf::from("a"), PathBuf::from("b")]
+    );
+}
+
+#[test]
+fn parse_empty_entries_posix() {
+    let search_path = SearchPath::parse(EMPTY_COMPONENTS_PATH, &ParseOptions::posix());
+    assert_eq!(
+        Vec::<PathBuf>::from(search_path),
+        vec![
+            PathBuf::from("."),
+            PathBuf::from("a"),
+            PathBuf::from("."),
+            PathBuf::from(" "),
+            PathBuf::from("b"),
+            PathBuf::from("."),
+        ]
+    );
+}
+
+#[test]
+fn parse_empty_entries_preserve() {
+    let options = ParseOptions::default().with_empty_entries(EmptyEntries::Preserve);
+    let search_path = SearchPath::parse(EMPTY_COMPONENTS_PATH, &options);
+    assert_eq!(
+        Vec::<PathBuf>::from(search_path),
+        vec![
+            PathBuf::from(""),
+            PathBuf::from("a"),
+            PathBuf::from(""),
+            PathBuf::from(" "),
+            PathBuf::from("b"),
+            PathBuf::from(""),
+        ]
+    );
+}
+
+#[test]
+fn parse_empty_string_posix() {
+    let search_path = SearchPath::parse("", &ParseOptions::posix());
+    assert_eq!(search_path.len(), 1);
+    assert!(search_path.contains_cwd());
+}
@@ -0,0 +1,56 @@
+use search_path::SearchPath;
+use std::path::PathBuf;
+
+fn make_exec_search_path() -> SearchPath {
+    vec![
+        "tests/exec/a",
+        "tests/exec/b",
+        "tests/exec/c",
+        "tests/exec/d",
+        "tests/exec/e",
+    ]
+    .into()
+}
+
+#[test]
+fn find_file_not_executable() {
+    let search_path = make_exec_search_path();
+    let result = search_path.find_file(&PathBuf::from("tool"));
+    assert_eq!(result, Some(PathBuf::from("tests/exec/a/tool")));
+}
+
+#[cfg(unix)]
+#[test]
+fn find_executable() {
+    let search_path = make_exec_search_path();
+    let result = search_path.find_executable(&PathBuf::from("tool"));
+    assert_eq!(result, Some(PathBuf::from("tests/exec/d/tool")));
+}
+
+#[cfg(unix)]
+#[test]
+fn find_all_executables() {
+    let search_path = make_exec_search_path();
+    let results = search_path.find_all_executables(&PathBuf::from("tool"));
+    assert_eq!(
+        results,
+        vec![
+            PathBuf::from("tests/exec/d/tool"),
+            PathBuf::from("tests/exec/e/tool")
+        ]
+    );
+}
+
+#[test]
+fn find_no_executable() {
+    let search_path = make_exec_search_path();
+    let result = search_path.find_executable(&PathBuf::from("none"));
+    assert!(result.is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn which_shell() {
+    assert!(search_path::which("sh").is_some());
+    assert!(search_path::which("unlikely-this-command-exists").is_none());
+}
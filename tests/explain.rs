@@ -0,0 +1,85 @@
+use search_path::{EntryKind, Outcome, SearchPath};
+use std::path::{Path, PathBuf};
+
+fn outcomes(search_path: &SearchPath, name: &str, kind: EntryKind) -> Vec<(PathBuf, Outcome)> {
+    search_path
+        .explain(Path::new(name), kind)
+        .iter()
+        .map(|candidate| (candidate.path().clone(), candidate.outcome()))
+        .collect()
+}
+
+#[test]
+fn explain_find_file() {
+    let search_path: SearchPath = vec!["tests", "tests/a", "tests/b", "tests/e/f/g"].into();
+    let trace = search_path.explain(Path::new("a.txt"), EntryKind::File);
+    assert_eq!(trace.found(), Some(&PathBuf::from("tests/a.txt")));
+    assert_eq!(
+        outcomes(&search_path, "a.txt", EntryKind::File),
+        vec![
+            (PathBuf::from("tests/a.txt"), Outcome::Found),
+            (PathBuf::from("tests/a/a.txt"), Outcome::NotFound),
+            (PathBuf::from("tests/b/a.txt"), Outcome::Shadowed),
+            (PathBuf::from("tests/e/f/g/a.txt"), Outcome::Shadowed),
+        ]
+    );
+}
+
+#[test]
+fn explain_find_directory() {
+    let search_path: SearchPath = vec!["tests/b", "tests"].into();
+    assert_eq!(
+        outcomes(&search_path, "a.txt", EntryKind::Directory),
+        vec![
+            (PathBuf::from("tests/b/a.txt"), Outcome::NotDirectory),
+            (PathBuf::from("tests/a.txt"), Outcome::NotDirectory),
+        ]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn explain_find_executable() {
+    let search_path: SearchPath = vec![
+        "tests/exec/a",
+        "tests/exec/b",
+        "tests/exec/c",
+        "tests/exec/d",
+        "tests/exec/e",
+    ]
+    .into();
+    assert_eq!(
+        outcomes(&search_path, "tool", EntryKind::Executable),
+        vec![
+            (PathBuf::from("tests/exec/a/tool"), Outcome::NotExecutable),
+            (PathBuf::from("tests/exec/b/tool"), Outcome::IsDirectory),
+            (PathBuf::from("tests/exec/c/tool"), Outcome::BrokenSymlink),
+            (PathBuf::from("tests/exec/d/tool"), Outcome::Found),
+            (PathBuf::from("tests/exec/e/tool"), Outcome::Shadowed),
+        ]
+    );
+}
+
+#[test]
+fn explain_not_found_display() {
+    let search_path: SearchPath = vec!["tests/a", "tests/b"].into();
+    let trace = search_path.explain(Path::new("none.txt"), EntryKind::Any);
+    assert!(trace.found().is_none());
+    assert_eq!(
+        trace.to_string(),
+        "search for any `none.txt`: not found\n  tests/a/none.txt: not found\n  tests/b/none.txt: not found\n"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn explain_find_symlink() {
+    let search_path: SearchPath = vec!["tests/kinds/a", "tests/kinds/b"].into();
+    assert_eq!(
+        outcomes(&search_path, "entry", EntryKind::Symlink),
+        vec![
+            (PathBuf::from("tests/kinds/a/entry"), Outcome::WrongKind),
+            (PathBuf::from("tests/kinds/b/entry"), Outcome::Found),
+        ]
+    );
+}
@@ -0,0 +1,84 @@
+use search_path::{Extensions, SearchPath};
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+fn make_ext_search_path() -> SearchPath {
+    let mut search_path: SearchPath = vec!["tests/ext/a", "tests/ext/b"].into();
+    search_path.set_extensions(".sh;.py".into());
+    search_path
+}
+
+#[test]
+fn parse_extensions() {
+    let extensions: Extensions = ".COM;;exe; .BAT ".into();
+    assert_eq!(
+        extensions.iter().cloned().collect::<Vec<OsString>>(),
+        vec![
+            OsString::from(".COM"),
+            OsString::from(".exe"),
+            OsString::from(".BAT")
+        ]
+    );
+}
+
+#[test]
+fn extensions_from_env_var() {
+    std::env::set_var("SEARCH_PATH_TEST_EXT", ".sh;.py");
+    let extensions = Extensions::new("SEARCH_PATH_TEST_EXT").unwrap();
+    assert_eq!(extensions.len(), 2);
+    assert!(Extensions::new("UNLIKELY_THIS_VAR_EXISTS").is_err());
+    assert!(Extensions::new_or_default("UNLIKELY_THIS_VAR_EXISTS").is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn extensions_from_env_var_not_unicode() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    std::env::set_var(
+        "SEARCH_PATH_TEST_EXT_NOT_UNICODE",
+        OsStr::from_bytes(b".sh; \xFF ;"),
+    );
+    let extensions = Extensions::new("SEARCH_PATH_TEST_EXT_NOT_UNICODE").unwrap();
+    assert_eq!(
+        extensions.iter().cloned().collect::<Vec<OsString>>(),
+        vec![
+            OsString::from(".sh"),
+            OsStr::from_bytes(b".\xFF").to_os_string()
+        ]
+    );
+}
+
+#[test]
+fn find_without_extensions() {
+    let search_path: SearchPath = vec!["tests/ext/a", "tests/ext/b"].into();
+    let result = search_path.find_file(&PathBuf::from("tool"));
+    assert_eq!(result, Some(PathBuf::from("tests/ext/b/tool")));
+}
+
+#[test]
+fn find_with_extensions() {
+    let search_path = make_ext_search_path();
+    let result = search_path.find_file(&PathBuf::from("tool"));
+    assert_eq!(result, Some(PathBuf::from("tests/ext/a/tool.py")));
+}
+
+#[test]
+fn find_all_with_extensions() {
+    let search_path = make_ext_search_path();
+    let results = search_path.find_all(&PathBuf::from("tool"));
+    assert_eq!(
+        results,
+        vec![
+            PathBuf::from("tests/ext/a/tool.py"),
+            PathBuf::from("tests/ext/b/tool"),
+            PathBuf::from("tests/ext/b/tool.sh"),
+        ]
+    );
+}
+
+#[test]
+fn find_no_file_with_extensions() {
+    let search_path = make_ext_search_path();
+    assert!(search_path.find(&PathBuf::from("none")).is_none());
+}
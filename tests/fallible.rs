@@ -0,0 +1,247 @@
+use search_path::{Entry, Error, LookupOptions, SearchPath};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+fn make_io_search_path() -> SearchPath {
+    vec!["tests/io/a", "tests/io/b"].into()
+}
+
+#[test]
+fn try_find_no_errors() {
+    let search_path: SearchPath = vec!["tests", "tests/b"].into();
+    let lookup = search_path
+        .try_find_file(Path::new("a.txt"), &LookupOptions::strict())
+        .unwrap();
+    assert!(!lookup.has_errors());
+    assert_eq!(lookup.into_result(), Some(PathBuf::from("tests/a.txt")));
+}
+
+#[test]
+fn try_find_not_found() {
+    let search_path: SearchPath = vec!["tests", "tests/a.txt", "tests/none"].into();
+    let lookup = search_path
+        .try_find(Path::new("none.txt"), &LookupOptions::strict())
+        .unwrap();
+    assert!(!lookup.has_errors());
+    assert!(lookup.result().is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn try_find_lenient_errors() {
+    let search_path = make_io_search_path();
+    let lookup = search_path
+        .try_find(Path::new("loop"), &LookupOptions::default())
+        .unwrap();
+    assert_eq!(lookup.errors().len(), 1);
+    match &lookup.errors()[0] {
+        Error::Io { path, .. } => assert_eq!(path, &PathBuf::from("tests/io/a/loop")),
+        _ => panic!("expected Error::Io"),
+    }
+    assert_eq!(lookup.result(), &Some(PathBuf::from("tests/io/b/loop")));
+}
+
+#[cfg(unix)]
+#[test]
+fn try_find_strict_errors() {
+    use std::error::Error as _;
+    let search_path = make_io_search_path();
+    let result = search_path.try_find(Path::new("loop"), &LookupOptions::strict());
+    let error = result.unwrap_err();
+    assert!(matches!(error, Error::Io { .. }));
+    assert!(error.source().is_some());
+}
+
+#[cfg(unix)]
+#[test]
+fn try_find_all_lenient_errors() {
+    let search_path = make_io_search_path();
+    let lookup = search_path
+        .try_find_all(Path::new("loop"), &LookupOptions::default())
+        .unwrap();
+    assert!(lookup.has_errors());
+    assert_eq!(lookup.into_result(), vec![PathBuf::from("tests/io/b/loop")]);
+}
+
+#[test]
+fn try_find_max_probes_strict() {
+    let search_path: SearchPath = vec!["tests/a", "tests/b", "tests"].into();
+    let options = LookupOptions::strict().with_max_probes(1);
+    match search_path.try_find(Path::new("a.txt"), &options) {
+        Err(Error::ProbeLimit { entry, probes }) => {
+            assert_eq!(entry, PathBuf::from("tests/b"));
+            assert_eq!(probes, 1);
+        }
+        _ => panic!("expected Error::ProbeLimit"),
+    }
+    let options = LookupOptions::strict().with_max_probes(2);
+    let lookup = search_path.try_find(Path::new("a.txt"), &options).unwrap();
+    assert_eq!(lookup.into_result(), Some(PathBuf::from("tests/b/a.txt")));
+}
+
+#[test]
+fn try_find_all_max_probes_partial() {
+    let search_path: SearchPath = vec!["tests/b", "tests/a", "tests"].into();
+    let options = LookupOptions::default().with_max_probes(2);
+    let lookup = search_path
+        .try_find_all(Path::new("a.txt"), &options)
+        .unwrap();
+    assert_eq!(lookup.errors().len(), 1);
+    assert!(matches!(
+        &lookup.errors()[0],
+        Error::ProbeLimit { entry, probes: 2 } if entry == &PathBuf::from("tests")
+    ));
+    assert_eq!(lookup.into_result(), vec![PathBuf::from("tests/b/a.txt")]);
+}
+
+#[test]
+fn try_find_max_probes_counts_walk() {
+    let search_path = SearchPath::from(vec![Entry::new("tests/e").with_recursive(true)]);
+    let options = LookupOptions::strict().with_max_probes(3);
+    match search_path.try_find(Path::new("x.txt"), &options) {
+        Err(Error::ProbeLimit { entry, probes: 3 }) => assert_eq!(entry, PathBuf::from("tests/e")),
+        other => panic!("expected Error::ProbeLimit, not {:?}", other),
+    }
+    let options = LookupOptions::strict().with_max_probes(1_000);
+    let lookup = search_path.try_find(Path::new("x.txt"), &options).unwrap();
+    assert_eq!(lookup.into_result(), Some(PathBuf::from("tests/e/f/x.txt")));
+}
+
+#[test]
+fn try_find_of_kind() {
+    let search_path: SearchPath = vec!["tests/a.txt", "tests", "tests/b"].into();
+    let options = LookupOptions::strict();
+    let lookup = search_path
+        .try_find_directory(Path::new("d"), &options)
+        .unwrap();
+    assert_eq!(
+        lookup.into_result(),
+        search_path.find_directory(Path::new("d"))
+    );
+    let lookup = search_path
+        .try_find_executable(Path::new("a.txt"), &options)
+        .unwrap();
+    assert_eq!(
+        lookup.into_result(),
+        search_path.find_executable(Path::new("a.txt"))
+    );
+}
+
+#[test]
+fn lookup_options_limit_find() {
+    let mut search_path: SearchPath = vec!["tests/a", "tests/b", "tests"].into();
+    search_path.set_lookup_options(LookupOptions::strict().with_max_probes(2));
+    assert!(search_path.lookup_options().is_some());
+    assert!(!search_path.lookup_options().unwrap().is_strict());
+    assert_eq!(
+        search_path.find(Path::new("a.txt")),
+        Some(PathBuf::from("tests/b/a.txt"))
+    );
+    assert_eq!(
+        search_path.find_all(Path::new("a.txt")),
+        vec![PathBuf::from("tests/b/a.txt")]
+    );
+    assert_eq!(search_path.matches(Path::new("a.txt")).count(), 1);
+    search_path.remove_lookup_options();
+    assert_eq!(search_path.find_all(Path::new("a.txt")).len(), 2);
+}
+
+#[test]
+fn lookup_options_limit_find_many() {
+    let mut search_path: SearchPath = vec!["tests/a", "tests/b", "tests"].into();
+    // Reading tests/a and checking b.txt use up the budget before tests/b is read.
+    search_path.set_lookup_options(LookupOptions::default().with_max_probes(2));
+    assert_eq!(
+        search_path.find_many(["b.txt", "a.txt"]),
+        HashMap::from([(PathBuf::from("b.txt"), PathBuf::from("tests/a/b.txt"))])
+    );
+    search_path.remove_lookup_options();
+    assert_eq!(search_path.find_many(["b.txt", "a.txt"]).len(), 2);
+}
+
+#[test]
+fn lookup_options_skip_unhealthy_in_find_many() {
+    let mut search_path: SearchPath = vec!["tests/a", "tests/b", "tests"].into();
+    let options = LookupOptions::default()
+        .with_deadline(Duration::ZERO)
+        .with_cool_down(Duration::from_secs(3600));
+    let _ = search_path.try_find(Path::new("b.txt"), &options).unwrap();
+    assert_eq!(
+        search_path.unhealthy_entries(),
+        vec![PathBuf::from("tests/a")]
+    );
+    assert_eq!(search_path.find_many(["b.txt"]).len(), 1);
+    search_path.set_lookup_options(LookupOptions::default().with_deadline(Duration::from_secs(60)));
+    assert!(search_path.find_many(["b.txt"]).is_empty());
+    assert_eq!(
+        search_path.find_many(["a.txt"]),
+        HashMap::from([(PathBuf::from("a.txt"), PathBuf::from("tests/b/a.txt"))])
+    );
+}
+
+#[test]
+fn lookup_options_limit_find_recursive() {
+    let mut search_path: SearchPath = vec!["tests/e"].into();
+    assert_eq!(
+        search_path.find_recursive(Path::new("x.txt"), 2),
+        Some(PathBuf::from("tests/e/f/x.txt"))
+    );
+    search_path.set_lookup_options(LookupOptions::default().with_max_probes(3));
+    assert_eq!(search_path.find_recursive(Path::new("x.txt"), 2), None);
+    assert!(search_path
+        .find_all_recursive(Path::new("x.txt"), 2)
+        .is_empty());
+}
+
+#[test]
+fn try_find_within_deadline() {
+    let search_path: SearchPath = vec!["tests/a", "tests/none", "tests/b", "tests"].into();
+    let options = LookupOptions::strict().with_deadline(Duration::from_secs(60));
+    let lookup = search_path
+        .try_find_all(Path::new("a.txt"), &options)
+        .unwrap();
+    assert!(!lookup.has_errors());
+    assert_eq!(
+        lookup.into_result(),
+        search_path.find_all(Path::new("a.txt"))
+    );
+    assert!(search_path.unhealthy_entries().is_empty());
+}
+
+#[test]
+fn try_find_deadline_cool_down() {
+    let search_path: SearchPath = vec!["tests/a", "tests/b", "tests"].into();
+    let options = LookupOptions::default()
+        .with_deadline(Duration::ZERO)
+        .with_cool_down(Duration::from_secs(3600));
+    let lookup = search_path.try_find(Path::new("a.txt"), &options).unwrap();
+    // A zero deadline has passed before the first check, so the first entry is blamed.
+    assert_eq!(lookup.result(), &None);
+    match lookup.errors() {
+        [Error::Timeout { entry, .. }] => assert_eq!(entry, Path::new("tests/a")),
+        errors => panic!("unexpected errors {:?}", errors),
+    }
+    assert_eq!(
+        search_path.unhealthy_entries(),
+        vec![PathBuf::from("tests/a")]
+    );
+
+    // Only tests/a holds b.txt, so a lookup skipping it finds nothing.
+    let options = LookupOptions::default().with_deadline(Duration::from_secs(60));
+    let lookup = search_path
+        .try_find_all(Path::new("b.txt"), &options)
+        .unwrap();
+    assert!(lookup.errors().is_empty());
+    assert!(lookup.result().is_empty());
+    assert_eq!(
+        search_path.find(Path::new("b.txt")),
+        Some(PathBuf::from("tests/a/b.txt"))
+    );
+
+    let copy = search_path.clone();
+    copy.clear_unhealthy();
+    assert!(search_path.unhealthy_entries().is_empty());
+    let lookup = search_path.try_find(Path::new("b.txt"), &options).unwrap();
+    assert_eq!(lookup.result(), &Some(PathBuf::from("tests/a/b.txt")));
+}
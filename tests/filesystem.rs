@@ -0,0 +1,274 @@
+use search_path::matcher::{Matcher, Name};
+use search_path::{
+    Entry, EntryKind, Error, Extensions, FileSystem, FileType, Index, LookupOptions, Metadata,
+    OsFileSystem, SearchPath,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+struct MemoryFileSystem {
+    entries: BTreeMap<PathBuf, (Metadata, &'static str)>,
+}
+
+impl MemoryFileSystem {
+    fn with_dir(mut self, path: &str) -> Self {
+        let _ = self.entries.insert(
+            PathBuf::from(path),
+            (Metadata::new(FileType::Directory), ""),
+        );
+        self
+    }
+
+    fn with_file(mut self, path: &str, contents: &'static str, executable: bool) -> Self {
+        let metadata = Metadata::new(FileType::File)
+            .with_size(contents.len() as u64)
+            .with_executable(executable);
+        let _ = self
+            .entries
+            .insert(PathBuf::from(path), (metadata, contents));
+        self
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        self.entries
+            .get(path)
+            .map(|(metadata, _)| metadata.clone())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(self
+            .entries
+            .keys()
+            .filter(|entry| entry.parent() == Some(path))
+            .cloned()
+            .collect())
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read>> {
+        match self.entries.get(path) {
+            Some((metadata, contents)) if metadata.is_file() => Ok(Box::new(contents.as_bytes())),
+            _ => Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.metadata(path).map(|_| path.to_path_buf())
+    }
+}
+
+fn make_file_system() -> MemoryFileSystem {
+    MemoryFileSystem::default()
+        .with_dir("/bin")
+        .with_dir("/usr")
+        .with_dir("/usr/bin")
+        .with_dir("/opt")
+        .with_dir("/opt/tool")
+        .with_dir("/opt/tool/bin")
+        .with_dir("/usr/bin/tool")
+        .with_file("/bin/sh", "#!", true)
+        .with_file("/usr/bin/sh", "#!", true)
+        .with_file("/usr/bin/readme", "text", false)
+        .with_file("/usr/bin/tool.sh", "#!", true)
+        .with_file("/opt/tool/bin/tool", "#!", true)
+}
+
+#[test]
+fn find_in_memory() {
+    let file_system = make_file_system();
+    let search_path: SearchPath = vec!["/bin", "/usr/bin", "/none"].into();
+    assert_eq!(
+        search_path.find_in(&file_system, Path::new("sh")),
+        Some(PathBuf::from("/bin/sh"))
+    );
+    assert_eq!(
+        search_path.find_all_in(&file_system, Path::new("sh")),
+        vec![PathBuf::from("/bin/sh"), PathBuf::from("/usr/bin/sh")]
+    );
+    assert_eq!(search_path.find_in(&file_system, Path::new("none")), None);
+    assert_eq!(
+        search_path.find_executable_in(&file_system, Path::new("readme")),
+        None
+    );
+    assert_eq!(
+        search_path.find_file_in(&file_system, Path::new("tool")),
+        None
+    );
+    assert_eq!(
+        search_path.find_directory_in(&file_system, Path::new("tool")),
+        Some(PathBuf::from("/usr/bin/tool"))
+    );
+}
+
+#[test]
+fn find_in_memory_with_extensions() {
+    let file_system = make_file_system();
+    let mut search_path: SearchPath = vec!["/bin", "/usr/bin"].into();
+    search_path.set_extensions(Extensions::from(".sh"));
+    assert_eq!(
+        search_path.find_executable_in(&file_system, Path::new("tool")),
+        Some(PathBuf::from("/usr/bin/tool.sh"))
+    );
+}
+
+#[test]
+fn find_in_memory_recursive() {
+    let file_system = make_file_system();
+    let search_path = SearchPath::from(vec![Entry::new("/opt").with_recursive(true)]);
+    assert_eq!(
+        search_path.find_all_of_kind_in(&file_system, Path::new("tool"), EntryKind::File),
+        vec![PathBuf::from("/opt/tool/bin/tool")]
+    );
+    assert_eq!(
+        search_path.find_all_glob_in(&file_system, "t*").unwrap(),
+        vec![
+            PathBuf::from("/opt/tool"),
+            PathBuf::from("/opt/tool/bin/tool")
+        ]
+    );
+    assert_eq!(
+        search_path.find_glob_in(&file_system, "**/tool").unwrap(),
+        Some(PathBuf::from("/opt/tool"))
+    );
+}
+
+#[test]
+fn find_in_memory_every_lookup() {
+    let file_system = make_file_system();
+    let search_path: SearchPath = vec!["/bin", "/usr/bin", "/opt"].into();
+    assert_eq!(
+        search_path.find_all_executables_in(&file_system, Path::new("sh")),
+        vec![PathBuf::from("/bin/sh"), PathBuf::from("/usr/bin/sh")]
+    );
+    assert_eq!(
+        search_path.find_recursive_in(&file_system, Path::new("tool"), 1),
+        Some(PathBuf::from("/usr/bin/tool"))
+    );
+    assert_eq!(
+        search_path.find_all_recursive_in(&file_system, Path::new("tool"), 2),
+        vec![
+            PathBuf::from("/usr/bin/tool"),
+            PathBuf::from("/opt/tool"),
+            PathBuf::from("/opt/tool/bin/tool"),
+        ]
+    );
+    assert_eq!(
+        search_path.find_matching_in(&file_system, &EntryKind::File.and(Name::new("r*"))),
+        Some(PathBuf::from("/usr/bin/readme"))
+    );
+    assert_eq!(
+        search_path.find_many_of_kind_in(&file_system, ["sh", "tool", "none"], EntryKind::File),
+        HashMap::from([(PathBuf::from("sh"), PathBuf::from("/bin/sh"))])
+    );
+    assert_eq!(
+        search_path.find_all_many_in(&file_system, ["tool"]),
+        HashMap::from([(
+            PathBuf::from("tool"),
+            vec![PathBuf::from("/usr/bin/tool"), PathBuf::from("/opt/tool")]
+        )])
+    );
+    let lookup = search_path
+        .try_find_executable_in(&file_system, Path::new("sh"), &LookupOptions::strict())
+        .unwrap();
+    assert_eq!(lookup.into_result(), Some(PathBuf::from("/bin/sh")));
+    let trace = search_path.explain_in(&file_system, Path::new("tool"), EntryKind::File);
+    assert_eq!(trace.found(), None);
+    assert_eq!(trace.iter().count(), 3);
+}
+
+#[test]
+fn find_in_memory_within_budget() {
+    let file_system = make_file_system();
+    let mut search_path: SearchPath = vec!["/none", "/bin", "/usr/bin"].into();
+    let options = LookupOptions::strict().with_max_probes(1);
+    assert!(matches!(
+        search_path.try_find_in(&file_system, Path::new("sh"), &options),
+        Err(Error::ProbeLimit { entry, probes: 1 }) if entry == Path::new("/bin")
+    ));
+    let options = LookupOptions::default()
+        .with_deadline(Duration::ZERO)
+        .with_cool_down(Duration::from_secs(3600));
+    let lookup = search_path
+        .try_find_all_in(&file_system, Path::new("sh"), &options)
+        .unwrap();
+    assert!(lookup.result().is_empty());
+    assert!(matches!(lookup.errors(), [Error::Timeout { .. }]));
+    assert_eq!(
+        search_path.unhealthy_entries(),
+        vec![PathBuf::from("/none")]
+    );
+    search_path.set_lookup_options(LookupOptions::default().with_max_probes(2));
+    assert_eq!(
+        search_path.find_all_in(&file_system, Path::new("sh")),
+        vec![PathBuf::from("/bin/sh")]
+    );
+}
+
+#[test]
+fn index_in_memory() {
+    let file_system = make_file_system();
+    let search_path = SearchPath::from(vec![Entry::new("/opt").with_recursive(true)]);
+    let index = Index::builder()
+        .with_search_path(&search_path)
+        .build_in(&file_system);
+    assert_eq!(
+        index
+            .directories_containing("tool".as_ref())
+            .collect::<Vec<_>>(),
+        vec![&PathBuf::from("/opt"), &PathBuf::from("/opt/tool/bin")]
+    );
+    assert!(!index.is_stale_in(&file_system));
+    assert!(index.stale_directories_in(&file_system).is_empty());
+    assert!(matches!(
+        index.save_in(&file_system, Path::new("/ls-R")),
+        Err(Error::Io { path, source }) if path == Path::new("/ls-R")
+            && source.kind() == io::ErrorKind::Unsupported
+    ));
+}
+
+#[test]
+fn find_in_os_matches_find() {
+    let search_path = SearchPath::from(vec![
+        Entry::new("tests"),
+        Entry::new("tests/b"),
+        Entry::new("tests/e").with_recursive(true),
+    ]);
+    for name in &["a.txt", "b.txt", "c.txt", "d", "x.txt", "none.txt"] {
+        let name = Path::new(name);
+        assert_eq!(
+            search_path.find_in(&OsFileSystem, name),
+            search_path.find(name)
+        );
+        assert_eq!(
+            search_path.find_all_in(&OsFileSystem, name),
+            search_path.find_all(name)
+        );
+        assert_eq!(
+            search_path.find_all_of_kind_in(&OsFileSystem, name, EntryKind::Directory),
+            search_path.find_all_directories(name)
+        );
+    }
+    assert_eq!(
+        search_path
+            .find_all_glob_in(&OsFileSystem, "*.txt")
+            .unwrap(),
+        search_path.find_all_glob("*.txt").unwrap()
+    );
+}
+
+#[test]
+fn os_metadata() {
+    let metadata = OsFileSystem.metadata(Path::new("tests/a.txt")).unwrap();
+    assert!(metadata.is_file());
+    assert!(metadata.modified().is_some());
+    assert_eq!(
+        metadata.size(),
+        std::fs::metadata("tests/a.txt").unwrap().len()
+    );
+    assert!(OsFileSystem.metadata(Path::new("tests")).unwrap().is_dir());
+}
@@ -124,3 +124,37 @@ fn find_filename_with_directory_partial() {
     let result = search_path.find_if_name_only(&PathBuf::from("g/a.txt"));
     assert!(result.is_none());
 }
+
+#[test]
+fn matches_full() {
+    let search_path = make_full_search_path();
+    let mut matches = search_path.matches(&PathBuf::from("a.txt"));
+    assert_eq!(matches.next(), Some(PathBuf::from("tests/a.txt")));
+    assert_eq!(matches.nth(1), Some(PathBuf::from("tests/e/f/g/a.txt")));
+    assert_eq!(matches.next(), None);
+}
+
+#[test]
+fn matches_files_full() {
+    let search_path = make_full_search_path();
+    let files: Vec<PathBuf> = search_path
+        .matches_files(&PathBuf::from("a.txt"))
+        .take(2)
+        .collect();
+    assert_eq!(
+        files,
+        vec![PathBuf::from("tests/a.txt"), PathBuf::from("tests/b/a.txt")]
+    );
+    assert_eq!(search_path.matches_files(&PathBuf::from("d")).next(), None);
+}
+
+#[test]
+fn matches_dirs_full() {
+    let search_path = make_full_search_path();
+    let dirs: Vec<PathBuf> = search_path.matches_dirs(&PathBuf::from("d")).collect();
+    assert_eq!(dirs, vec![PathBuf::from("tests/b/d")]);
+    assert_eq!(
+        search_path.matches_dirs(&PathBuf::from("a.txt")).next(),
+        None
+    );
+}
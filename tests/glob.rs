@@ -0,0 +1,121 @@
+use search_path::{Error, SearchPath};
+use std::path::PathBuf;
+
+fn make_glob_search_path() -> SearchPath {
+    vec!["tests/b", "tests/e/f"].into()
+}
+
+fn paths(vs: &[&str]) -> Vec<PathBuf> {
+    vs.iter().map(PathBuf::from).collect()
+}
+
+#[test]
+fn find_glob_star() {
+    let search_path = make_glob_search_path();
+    assert_eq!(
+        search_path.find_all_glob("*.txt").unwrap(),
+        paths(&["tests/b/a.txt", "tests/b/c.txt", "tests/e/f/x.txt"])
+    );
+}
+
+#[test]
+fn find_glob_first() {
+    let search_path = make_glob_search_path();
+    assert_eq!(
+        search_path.find_glob("?.txt").unwrap(),
+        Some(PathBuf::from("tests/b/a.txt"))
+    );
+    assert_eq!(
+        search_path.find_glob("x.*").unwrap(),
+        Some(PathBuf::from("tests/e/f/x.txt"))
+    );
+    assert_eq!(search_path.find_glob("none*").unwrap(), None);
+}
+
+#[test]
+fn find_glob_class() {
+    let search_path = make_glob_search_path();
+    assert_eq!(
+        search_path.find_all_glob("[!a]*.txt").unwrap(),
+        paths(&["tests/b/c.txt", "tests/e/f/x.txt"])
+    );
+    assert_eq!(
+        search_path.find_all_glob("g/[a-x].txt").unwrap(),
+        paths(&["tests/e/f/g/a.txt"])
+    );
+}
+
+#[test]
+fn find_glob_alternatives() {
+    let search_path = make_glob_search_path();
+    assert_eq!(
+        search_path.find_all_glob("{x,a}.txt").unwrap(),
+        paths(&["tests/b/a.txt", "tests/e/f/x.txt"])
+    );
+    assert_eq!(
+        search_path.find_all_glob("{d,g/{a,y}.txt}").unwrap(),
+        paths(&["tests/b/d", "tests/e/f/g/a.txt", "tests/e/f/g/y.txt"])
+    );
+}
+
+#[test]
+fn find_glob_recursive() {
+    let search_path = make_glob_search_path();
+    assert_eq!(
+        search_path.find_all_glob("**/no-op.txt").unwrap(),
+        paths(&["tests/b/d/no-op.txt", "tests/e/f/c/no-op.txt"])
+    );
+    let search_path: SearchPath = vec!["tests/e/f"].into();
+    assert_eq!(
+        search_path.find_all_glob("**/*.txt").unwrap(),
+        paths(&[
+            "tests/e/f/c/no-op.txt",
+            "tests/e/f/g/a.txt",
+            "tests/e/f/g/y.txt",
+            "tests/e/f/x.txt"
+        ])
+    );
+}
+
+#[test]
+fn find_glob_hidden() {
+    let search_path: SearchPath = vec!["tests/b"].into();
+    assert!(!search_path
+        .find_all_glob("*")
+        .unwrap()
+        .contains(&PathBuf::from("tests/b/.hidden.txt")));
+    assert_eq!(
+        search_path.find_all_glob(".*.txt").unwrap(),
+        paths(&["tests/b/.hidden.txt"])
+    );
+}
+
+#[test]
+fn find_glob_absolute() {
+    let search_path = make_glob_search_path();
+    assert!(matches!(
+        search_path.find_glob("/usr/*/sh"),
+        Err(Error::InvalidPattern { .. })
+    ));
+    assert!(matches!(
+        search_path.find_all_glob("{x.txt,/tmp/*}"),
+        Err(Error::InvalidPattern { .. })
+    ));
+}
+
+#[cfg(unix)]
+#[test]
+fn find_glob_skips_broken_symlinks() {
+    let dir = std::env::temp_dir().join(format!("search_path-glob-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("tool-a"), "").unwrap();
+    std::os::unix::fs::symlink(dir.join("none"), dir.join("tool-b")).unwrap();
+    let search_path: SearchPath = vec![dir.clone()].into();
+    let globbed = search_path.find_all_glob("tool-*").unwrap();
+    let literal = search_path.find_glob("tool-b").unwrap();
+    let found = search_path.find(std::path::Path::new("tool-b"));
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(globbed, vec![dir.join("tool-a")]);
+    assert_eq!(literal, found);
+    assert_eq!(literal, None);
+}
@@ -0,0 +1,165 @@
+use search_path::{Entry, Error, Index, ParseOptions, PathStyle, SearchPath};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+mod common;
+use common::make_temp_dir;
+
+fn kpathsea_options() -> ParseOptions {
+    ParseOptions::default()
+        .with_style(PathStyle::Unix)
+        .with_kpathsea(true)
+}
+
+#[test]
+fn build_index() {
+    let search_path = SearchPath::parse("tests/walk//:tests/a", &kpathsea_options());
+    let index = Index::builder().with_search_path(&search_path).build();
+    assert_eq!(
+        index.roots().collect::<Vec<&Entry>>(),
+        vec![
+            &Entry::new("tests/walk").with_recursive(true),
+            &Entry::new("tests/a")
+        ]
+    );
+    assert_eq!(
+        index.directories().collect::<Vec<&PathBuf>>(),
+        vec![
+            &PathBuf::from("tests/walk"),
+            &PathBuf::from("tests/walk/.hidden"),
+            &PathBuf::from("tests/walk/visible"),
+            &PathBuf::from("tests/a"),
+        ]
+    );
+    assert_eq!(
+        index
+            .directories_containing(OsStr::new("target.txt"))
+            .collect::<Vec<&PathBuf>>(),
+        vec![
+            &PathBuf::from("tests/walk/.hidden"),
+            &PathBuf::from("tests/walk/visible"),
+        ]
+    );
+    assert_eq!(
+        index.directories_containing(OsStr::new("none.txt")).count(),
+        0
+    );
+    assert!(!index.is_stale());
+}
+
+#[test]
+fn save_and_load_index() {
+    let dir = make_temp_dir("index-save");
+    let index_file = dir.join("ls-R.idx");
+    let search_path = SearchPath::parse("tests/walk//:tests/a:tests/none", &kpathsea_options());
+    let index = Index::builder().with_search_path(&search_path).build();
+    index.save(&index_file).unwrap();
+    let loaded = Index::load(&index_file);
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(loaded.unwrap(), index);
+}
+
+#[test]
+fn load_invalid_index() {
+    let dir = make_temp_dir("index-invalid");
+    let index_file = dir.join("ls-R.idx");
+    Index::default().save(&index_file).unwrap();
+    let empty = Index::load(&index_file);
+    let mut bytes = fs::read(&index_file).unwrap();
+    fs::write(&index_file, &bytes[..10]).unwrap();
+    let truncated = Index::load(&index_file);
+    bytes[0] = b'X';
+    fs::write(&index_file, &bytes).unwrap();
+    let corrupt = Index::load(&index_file);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(empty.unwrap(), Index::default());
+    assert!(matches!(truncated, Err(Error::Io { path, .. }) if path == index_file));
+    assert!(matches!(corrupt, Err(Error::Io { path, .. }) if path == index_file));
+    assert!(matches!(
+        Index::load(Path::new("tests/a/b.txt")),
+        Err(Error::Io { .. })
+    ));
+}
+
+#[test]
+fn find_with_index_only_entry() {
+    let mut search_path = SearchPath::parse("!!tests/walk//:tests/a", &kpathsea_options());
+    assert_eq!(search_path.find(Path::new("target.txt")), None);
+    search_path.set_index(Index::builder().with_search_path(&search_path).build());
+    assert_eq!(
+        search_path.find_all(Path::new("target.txt")),
+        vec![
+            PathBuf::from("tests/walk/.hidden/target.txt"),
+            PathBuf::from("tests/walk/visible/target.txt"),
+        ]
+    );
+    assert_eq!(
+        search_path.find_all_glob("*.txt").unwrap(),
+        vec![
+            PathBuf::from("tests/walk/.hidden/target.txt"),
+            PathBuf::from("tests/walk/visible/target.txt"),
+            PathBuf::from("tests/a/b.txt"),
+        ]
+    );
+    search_path.remove_index();
+    assert!(search_path.index().is_none());
+    assert_eq!(search_path.find(Path::new("target.txt")), None);
+}
+
+#[test]
+fn find_falls_back_to_disk() {
+    let dir = make_temp_dir("index-find");
+    let mut search_path: SearchPath = vec![dir.clone()].into();
+    search_path.set_index(Index::builder().with_search_path(&search_path).build());
+    fs::write(dir.join("new.txt"), "").unwrap();
+    let before = search_path.find(Path::new("new.txt"));
+    search_path.set_index(search_path.index().unwrap().rebuild());
+    let after = search_path.find(Path::new("new.txt"));
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(before, Some(dir.join("new.txt")));
+    assert_eq!(after, Some(dir.join("new.txt")));
+}
+
+#[test]
+fn find_index_only_does_not_fall_back() {
+    let dir = make_temp_dir("index-only-find");
+    let mut search_path: SearchPath = vec![Entry::new(dir.clone()).with_index_only(true)].into();
+    search_path.set_index(Index::builder().with_search_path(&search_path).build());
+    fs::write(dir.join("new.txt"), "").unwrap();
+    let before = search_path.find(Path::new("new.txt"));
+    search_path.set_index(search_path.index().unwrap().rebuild());
+    let after = search_path.find(Path::new("new.txt"));
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(before, None);
+    assert_eq!(after, Some(dir.join("new.txt")));
+}
+
+#[cfg(unix)]
+#[test]
+fn index_staleness() {
+    let dir = make_temp_dir("index-stale");
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    let search_path: SearchPath = vec![Entry::new(dir.clone()).with_recursive(true)].into();
+    let index = Index::builder().with_search_path(&search_path).build();
+    let fresh = index.is_stale();
+    fs::write(dir.join("sub/new.txt"), "").unwrap();
+    // Set the modification time explicitly, file systems with a coarse granularity may not
+    // otherwise record a change.
+    fs::File::open(dir.join("sub"))
+        .unwrap()
+        .set_modified(UNIX_EPOCH + Duration::from_secs(1_000_000))
+        .unwrap();
+    let stale = index
+        .stale_directories()
+        .into_iter()
+        .cloned()
+        .collect::<Vec<PathBuf>>();
+    let rebuilt = index.rebuild().is_stale();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(!fresh);
+    assert_eq!(stale, vec![dir.join("sub")]);
+    assert!(!rebuilt);
+}
@@ -0,0 +1,75 @@
+use search_path::{EntryKind, SearchPath};
+use std::path::{Path, PathBuf};
+
+fn make_kinds_search_path() -> SearchPath {
+    vec!["tests/kinds/a", "tests/kinds/b", "tests/kinds/c"].into()
+}
+
+#[test]
+fn find_all_files() {
+    let search_path = make_kinds_search_path();
+    assert_eq!(
+        search_path.find_all_files(Path::new("entry")),
+        vec![
+            PathBuf::from("tests/kinds/a/entry"),
+            PathBuf::from("tests/kinds/b/entry")
+        ]
+    );
+}
+
+#[test]
+fn find_all_directories() {
+    let search_path = make_kinds_search_path();
+    assert_eq!(
+        search_path.find_all_directories(Path::new("entry")),
+        vec![PathBuf::from("tests/kinds/c/entry")]
+    );
+}
+
+#[test]
+fn find_all_of_kind_any() {
+    let search_path = make_kinds_search_path();
+    assert_eq!(
+        search_path.find_all_of_kind(Path::new("entry"), EntryKind::Any),
+        search_path.find_all(Path::new("entry"))
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn find_all_of_kind_symlink() {
+    let search_path = make_kinds_search_path();
+    assert_eq!(
+        search_path.find_all_of_kind(Path::new("entry"), EntryKind::Symlink),
+        vec![PathBuf::from("tests/kinds/b/entry")]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn find_all_of_kind_char_device() {
+    let search_path: SearchPath = vec!["/dev"].into();
+    assert_eq!(
+        search_path.find_all_of_kind(Path::new("null"), EntryKind::CharDevice),
+        vec![PathBuf::from("/dev/null")]
+    );
+    assert!(search_path
+        .find_all_of_kind(Path::new("null"), EntryKind::BlockDevice)
+        .is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn find_all_of_kind_socket() {
+    use std::os::unix::net::UnixListener;
+    let dir = std::env::temp_dir().join(format!("search_path-kinds-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let socket = dir.join("test.sock");
+    let _listener = UnixListener::bind(&socket).unwrap();
+    let search_path: SearchPath = vec![dir.clone()].into();
+    let results = search_path.find_all_of_kind(Path::new("test.sock"), EntryKind::Socket);
+    let fifos = search_path.find_all_of_kind(Path::new("test.sock"), EntryKind::Fifo);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(results, vec![socket]);
+    assert!(fifos.is_empty());
+}
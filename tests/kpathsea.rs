@@ -0,0 +1,139 @@
+use search_path::{Entry, ParseOptions, PathStyle, SearchPath};
+use std::path::{Path, PathBuf};
+
+fn kpathsea_options() -> ParseOptions {
+    ParseOptions::default()
+        .with_style(PathStyle::Unix)
+        .with_kpathsea(true)
+}
+
+#[test]
+fn parse_kpathsea_entries() {
+    let search_path = SearchPath::parse(
+        "tests/e//:!!tests/a:!!tests/b//:tests/b",
+        &kpathsea_options(),
+    );
+    let entries: Vec<&Entry> = search_path.entries().collect();
+    assert_eq!(
+        entries,
+        vec![
+            &Entry::new("tests/e").with_recursive(true),
+            &Entry::new("tests/a").with_index_only(true),
+            &Entry::new("tests/b")
+                .with_recursive(true)
+                .with_index_only(true),
+            &Entry::new("tests/b"),
+        ]
+    );
+    assert!(search_path.contains(&PathBuf::from("tests/e")));
+    assert_eq!(search_path.iter().next(), Some(&PathBuf::from("tests/e")));
+}
+
+#[test]
+fn parse_kpathsea_root() {
+    let search_path = SearchPath::parse("//", &kpathsea_options());
+    assert_eq!(
+        search_path.entries().next(),
+        Some(&Entry::new("/").with_recursive(true))
+    );
+}
+
+#[test]
+fn parse_without_kpathsea() {
+    let search_path = SearchPath::parse(
+        "tests/e//:!!tests/a",
+        &ParseOptions::default().with_style(PathStyle::Unix),
+    );
+    assert_eq!(
+        search_path.entries().collect::<Vec<&Entry>>(),
+        vec![&Entry::new("tests/e//"), &Entry::new("!!tests/a")]
+    );
+}
+
+#[test]
+fn display_kpathsea_round_trip() {
+    let search_path = SearchPath::parse("!!tests/e//:tests/a:tests/b//", &kpathsea_options());
+    let displayed = search_path.display_with_style(PathStyle::Unix).to_string();
+    assert_eq!(displayed, "!!tests/e//:tests/a:tests/b//");
+    assert_eq!(
+        SearchPath::parse(&displayed, &kpathsea_options()),
+        search_path
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn to_os_string_kpathsea() {
+    let search_path = SearchPath::parse("tests/e//:!!tests/a", &kpathsea_options());
+    assert_eq!(
+        search_path.to_os_string().unwrap(),
+        std::ffi::OsString::from("tests/e:tests/a")
+    );
+    assert_eq!(
+        search_path.to_env_value().unwrap(),
+        std::ffi::OsString::from("tests/e:tests/a")
+    );
+    assert_eq!(
+        search_path.to_kpathsea_os_string().unwrap(),
+        std::ffi::OsString::from("tests/e//:!!tests/a")
+    );
+    assert_eq!(
+        SearchPath::parse(
+            search_path
+                .to_kpathsea_os_string()
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            &kpathsea_options()
+        ),
+        search_path
+    );
+}
+
+#[test]
+fn find_in_recursive_entry() {
+    let search_path = SearchPath::parse("tests/walk//", &kpathsea_options());
+    assert_eq!(
+        search_path.find(Path::new("target.txt")),
+        Some(PathBuf::from("tests/walk/.hidden/target.txt"))
+    );
+    assert_eq!(
+        search_path.find_all(Path::new("target.txt")),
+        vec![
+            PathBuf::from("tests/walk/.hidden/target.txt"),
+            PathBuf::from("tests/walk/visible/target.txt"),
+        ]
+    );
+}
+
+#[test]
+fn find_in_non_recursive_entry() {
+    let search_path = SearchPath::parse("tests/walk", &kpathsea_options());
+    assert_eq!(search_path.find(Path::new("target.txt")), None);
+}
+
+#[test]
+fn find_recursive_entry_precedence() {
+    let search_path = SearchPath::parse("tests/b//:tests/a", &kpathsea_options());
+    assert_eq!(
+        search_path.find_all_files(Path::new("no-op.txt")),
+        vec![PathBuf::from("tests/b/d/no-op.txt")]
+    );
+    assert_eq!(
+        search_path.find_all_glob("*.txt").unwrap(),
+        vec![
+            PathBuf::from("tests/b/a.txt"),
+            PathBuf::from("tests/b/c.txt"),
+            PathBuf::from("tests/b/d/no-op.txt"),
+            PathBuf::from("tests/a/b.txt"),
+        ]
+    );
+}
+
+#[test]
+fn find_skips_index_only_entry() {
+    let search_path = SearchPath::parse("!!tests/walk//:!!tests/a", &kpathsea_options());
+    assert_eq!(search_path.find(Path::new("target.txt")), None);
+    assert_eq!(search_path.find(Path::new("b.txt")), None);
+    assert!(search_path.find_all_glob("*").unwrap().is_empty());
+}
@@ -0,0 +1,97 @@
+use search_path::matcher::{Extension, Matcher, Modified, Name, Size};
+use search_path::{EntryKind, SearchPath};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+fn make_matcher_search_path() -> SearchPath {
+    vec!["tests/b", "tests/e/f", "tests/e/f/g"].into()
+}
+
+fn paths(vs: &[&str]) -> Vec<PathBuf> {
+    vs.iter().map(PathBuf::from).collect()
+}
+
+#[test]
+fn find_matching_kind() {
+    let search_path = make_matcher_search_path();
+    assert_eq!(
+        search_path
+            .find_all_matching(&EntryKind::Directory)
+            .collect::<Vec<PathBuf>>(),
+        paths(&["tests/b/d", "tests/e/f/c", "tests/e/f/g"])
+    );
+}
+
+#[test]
+fn find_matching_name() {
+    let search_path = make_matcher_search_path();
+    assert_eq!(
+        search_path.find_matching(&Name::new("[x-y].txt")),
+        Some(PathBuf::from("tests/e/f/x.txt"))
+    );
+}
+
+#[test]
+fn find_matching_and_not() {
+    let search_path = make_matcher_search_path();
+    let matcher = EntryKind::File
+        .and(Extension::new("txt"))
+        .and(Name::new("a.*").not());
+    assert_eq!(
+        search_path
+            .find_all_matching(&matcher)
+            .collect::<Vec<PathBuf>>(),
+        paths(&[
+            "tests/b/.hidden.txt",
+            "tests/b/c.txt",
+            "tests/e/f/x.txt",
+            "tests/e/f/g/y.txt"
+        ])
+    );
+}
+
+#[test]
+fn find_matching_or() {
+    let search_path = make_matcher_search_path();
+    let matcher = Name::new("c.txt").or(Name::new("y.txt"));
+    assert_eq!(
+        search_path
+            .find_all_matching(&matcher)
+            .collect::<Vec<PathBuf>>(),
+        paths(&["tests/b/c.txt", "tests/e/f/g/y.txt"])
+    );
+}
+
+#[test]
+fn find_matching_size_and_modified() {
+    let search_path = make_matcher_search_path();
+    let matcher = EntryKind::File
+        .and(Size::new(..1))
+        .and(Modified::new(..SystemTime::now() + Duration::from_secs(60)));
+    assert_eq!(
+        search_path.find_matching(&matcher),
+        Some(PathBuf::from("tests/b/.hidden.txt"))
+    );
+    assert!(search_path.find_matching(&Size::new(1_000_000..)).is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn find_matching_mode() {
+    use search_path::matcher::Mode;
+    let search_path: SearchPath = vec!["tests/exec/a", "tests/exec/d"].into();
+    let matcher = EntryKind::File.and(Mode::new(0o111));
+    assert_eq!(
+        search_path.find_matching(&matcher),
+        Some(PathBuf::from("tests/exec/d/tool"))
+    );
+}
+
+#[test]
+fn find_matching_lazy() {
+    let search_path = make_matcher_search_path();
+    let boxed: Box<dyn Matcher> = Box::new(EntryKind::File);
+    let mut matches = search_path.find_all_matching(&boxed);
+    assert_eq!(matches.next(), Some(PathBuf::from("tests/b/.hidden.txt")));
+    assert_eq!(matches.nth(2), Some(PathBuf::from("tests/e/f/x.txt")));
+}
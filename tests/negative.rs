@@ -0,0 +1,158 @@
+use search_path::{Entry, Index, NegativeCache, SearchPath};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+mod common;
+use common::make_temp_dir;
+
+fn make_search_path() -> SearchPath {
+    let mut search_path: SearchPath = vec!["tests", "tests/a", "tests/b", "tests/none"].into();
+    search_path.set_negative_cache(NegativeCache::default());
+    search_path
+}
+
+#[test]
+fn repeated_miss_is_cached() {
+    let search_path = make_search_path();
+    for _ in 0..3 {
+        assert_eq!(search_path.find(Path::new("none.txt")), None);
+        assert!(search_path.find_all(Path::new("none.txt")).is_empty());
+    }
+    let stats = search_path.negative_cache().unwrap().stats();
+    assert_eq!(stats.hits(), 5);
+    assert_eq!(stats.misses(), 1);
+    assert_eq!(stats.invalidations(), 0);
+}
+
+#[test]
+fn found_is_not_cached() {
+    let search_path = make_search_path();
+    for _ in 0..2 {
+        assert_eq!(
+            search_path.find(Path::new("a.txt")),
+            Some(PathBuf::from("tests/a.txt"))
+        );
+    }
+    assert_eq!(search_path.find_directory(Path::new("a.txt")), None);
+    assert_eq!(search_path.find_directory(Path::new("a.txt")), None);
+    let stats = search_path.negative_cache().unwrap().stats();
+    assert_eq!((stats.hits(), stats.misses()), (1, 1));
+}
+
+#[test]
+fn changed_search_path_invalidates() {
+    let mut search_path = make_search_path();
+    let copy = search_path.clone();
+    assert_eq!(search_path.find(Path::new("x.txt")), None);
+    assert_eq!(copy.find(Path::new("x.txt")), None);
+    search_path.append(PathBuf::from("tests/e/f"));
+    assert_eq!(
+        search_path.find(Path::new("x.txt")),
+        Some(PathBuf::from("tests/e/f/x.txt"))
+    );
+    let stats = search_path.negative_cache().unwrap().stats();
+    assert_eq!(
+        (stats.hits(), stats.misses(), stats.invalidations()),
+        (1, 1, 1)
+    );
+}
+
+#[test]
+fn clear_invalidates() {
+    let search_path = make_search_path();
+    let cache = search_path.negative_cache().unwrap();
+    cache.clear();
+    assert_eq!(search_path.find(Path::new("none.txt")), None);
+    cache.clear();
+    assert_eq!(search_path.find(Path::new("none.txt")), None);
+    assert_eq!(cache.stats().invalidations(), 1);
+    assert_eq!(cache.stats().misses(), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn changed_directory_invalidates() {
+    use std::fs::File;
+    use std::time::UNIX_EPOCH;
+
+    let dir = make_temp_dir("negative");
+    File::open(&dir)
+        .unwrap()
+        .set_modified(UNIX_EPOCH + Duration::from_secs(1_000_000))
+        .unwrap();
+    let mut search_path = SearchPath::from(vec![dir.clone()]);
+    search_path
+        .set_negative_cache(NegativeCache::default().with_validation_interval(Duration::ZERO));
+
+    assert_eq!(search_path.find_file(Path::new("tool")), None);
+    assert_eq!(search_path.find_file(Path::new("tool")), None);
+    fs::write(dir.join("tool"), "").unwrap();
+    let found = search_path.find_file(Path::new("tool"));
+    let stats = search_path.negative_cache().unwrap().stats();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(found, Some(dir.join("tool")));
+    assert_eq!(
+        (stats.hits(), stats.misses(), stats.invalidations()),
+        (1, 1, 1)
+    );
+}
+
+#[test]
+fn validation_interval_defers_checks() {
+    let dir = make_temp_dir("negative-interval");
+    let mut search_path = SearchPath::from(vec![dir.clone()]);
+    search_path.set_negative_cache(
+        NegativeCache::default().with_validation_interval(Duration::from_secs(3600)),
+    );
+
+    assert_eq!(search_path.find_file(Path::new("tool")), None);
+    assert_eq!(search_path.find_file(Path::new("tool")), None);
+    fs::write(dir.join("tool"), "").unwrap();
+    let deferred = search_path.find_file(Path::new("tool"));
+    search_path.negative_cache().unwrap().clear();
+    let found = search_path.find_file(Path::new("tool"));
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(deferred, None);
+    assert_eq!(found, Some(dir.join("tool")));
+}
+
+#[cfg(unix)]
+#[test]
+fn changed_directory_invalidates_only_its_misses() {
+    use std::fs::File;
+    use std::time::UNIX_EPOCH;
+
+    let dir = make_temp_dir("negative-directory");
+    for (sub, name) in [("one", "x"), ("two", "y")] {
+        fs::create_dir_all(dir.join(sub)).unwrap();
+        fs::write(dir.join(sub).join(name), "").unwrap();
+    }
+    let mut search_path = SearchPath::from(vec![Entry::new(dir.clone()).with_recursive(true)]);
+    search_path.set_index(Index::builder().with_search_path(&search_path).build());
+    search_path
+        .set_negative_cache(NegativeCache::default().with_validation_interval(Duration::ZERO));
+    // The index still records each name in its own directory, so each miss searches only that
+    // directory.
+    fs::remove_file(dir.join("one/x")).unwrap();
+    fs::remove_file(dir.join("two/y")).unwrap();
+    File::open(dir.join("two"))
+        .unwrap()
+        .set_modified(UNIX_EPOCH + Duration::from_secs(1_000_000))
+        .unwrap();
+
+    assert_eq!(search_path.find_file(Path::new("x")), None);
+    assert_eq!(search_path.find_file(Path::new("y")), None);
+    fs::write(dir.join("two/z"), "").unwrap();
+    assert_eq!(search_path.find_file(Path::new("x")), None);
+    assert_eq!(search_path.find_file(Path::new("y")), None);
+    let stats = search_path.negative_cache().unwrap().stats();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        (stats.hits(), stats.misses(), stats.invalidations()),
+        (1, 3, 1)
+    );
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "parallel")]
+
+use search_path::{EntryKind, SearchPath};
+use std::path::{Path, PathBuf};
+
+const NAMES: &[&str] = &[
+    "a.txt",
+    "b.txt",
+    "c.txt",
+    "d",
+    "x.txt",
+    "d/no-op.txt",
+    "none.txt",
+];
+
+fn make_search_path() -> SearchPath {
+    vec![
+        "tests",
+        "tests/a",
+        "tests/b",
+        "tests/c",
+        "tests/none",
+        "tests/e/f",
+        "tests/e/f/g",
+    ]
+    .into()
+}
+
+#[test]
+fn find_parallel_matches_find() {
+    let search_path = make_search_path();
+    for name in NAMES {
+        let name = Path::new(name);
+        assert_eq!(search_path.find_parallel(name), search_path.find(name));
+        assert_eq!(
+            search_path.find_parallel_of_kind(name, EntryKind::Directory),
+            search_path.find_directory(name)
+        );
+    }
+}
+
+#[test]
+fn find_parallel_earliest_entry() {
+    let search_path: SearchPath = vec!["tests/e/f/g", "tests/b", "tests"].into();
+    for _ in 0..16 {
+        assert_eq!(
+            search_path.find_parallel(Path::new("a.txt")),
+            Some(PathBuf::from("tests/e/f/g/a.txt"))
+        );
+    }
+}
+
+#[test]
+fn find_all_parallel_matches_find_all() {
+    let search_path = make_search_path();
+    for name in NAMES {
+        let name = Path::new(name);
+        assert_eq!(
+            search_path.find_all_parallel(name),
+            search_path.find_all(name)
+        );
+        assert_eq!(
+            search_path.find_all_parallel_of_kind(name, EntryKind::File),
+            search_path.find_all_files(name)
+        );
+    }
+}
+
+#[test]
+fn find_parallel_empty() {
+    let search_path = SearchPath::default();
+    assert_eq!(search_path.find_parallel(Path::new("a.txt")), None);
+    assert!(search_path.find_all_parallel(Path::new("a.txt")).is_empty());
+}
+
+#[test]
+fn find_parallel_more_entries_than_workers() {
+    let entries: Vec<&str> = ["tests/none", "tests/a", "tests/b", "tests/e/f"]
+        .iter()
+        .cycle()
+        .take(256)
+        .cloned()
+        .collect();
+    let search_path: SearchPath = entries.into();
+    for name in NAMES {
+        let name = Path::new(name);
+        assert_eq!(search_path.find_parallel(name), search_path.find(name));
+        assert_eq!(
+            search_path.find_all_parallel(name),
+            search_path.find_all(name)
+        );
+    }
+}
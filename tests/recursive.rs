@@ -0,0 +1,73 @@
+use search_path::{SearchPath, WalkOptions};
+use std::path::{Path, PathBuf};
+
+mod common;
+use common::make_temp_dir;
+
+fn make_recursive_search_path() -> SearchPath {
+    vec!["tests/e", "tests/b"].into()
+}
+
+#[test]
+fn find_recursive_depth() {
+    let search_path = make_recursive_search_path();
+    assert_eq!(
+        search_path.find_recursive(Path::new("a.txt"), 0),
+        Some(PathBuf::from("tests/b/a.txt"))
+    );
+    assert_eq!(
+        search_path.find_recursive(Path::new("a.txt"), 1),
+        Some(PathBuf::from("tests/b/a.txt"))
+    );
+    assert_eq!(
+        search_path.find_recursive(Path::new("a.txt"), 2),
+        Some(PathBuf::from("tests/e/f/g/a.txt"))
+    );
+    assert_eq!(search_path.find_recursive(Path::new("none.txt"), 8), None);
+}
+
+#[test]
+fn find_all_recursive_precedence() {
+    let search_path = make_recursive_search_path();
+    assert_eq!(
+        search_path.find_all_recursive(Path::new("no-op.txt"), 5),
+        vec![
+            PathBuf::from("tests/e/f/c/no-op.txt"),
+            PathBuf::from("tests/b/d/no-op.txt")
+        ]
+    );
+}
+
+#[test]
+fn find_recursive_hidden() {
+    let search_path: SearchPath = vec!["tests/walk"].into();
+    assert_eq!(
+        search_path.find_recursive(Path::new("target.txt"), 1),
+        Some(PathBuf::from("tests/walk/.hidden/target.txt"))
+    );
+    let options = WalkOptions::default().with_skip_hidden(true);
+    assert_eq!(
+        search_path.find_recursive_with(Path::new("target.txt"), &options),
+        Some(PathBuf::from("tests/walk/visible/target.txt"))
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn find_all_recursive_symlink_loop() {
+    use std::fs;
+
+    let dir = make_temp_dir("walk-loop");
+    fs::create_dir_all(dir.join("visible")).unwrap();
+    fs::write(dir.join("visible/target.txt"), "").unwrap();
+    std::os::unix::fs::symlink("..", dir.join("visible/loop")).unwrap();
+    let search_path: SearchPath = vec![dir.clone()].into();
+    let found =
+        search_path.find_all_recursive_with(Path::new("target.txt"), &WalkOptions::default());
+    let options = WalkOptions::default().with_follow_symlinks(false);
+    let not_followed = search_path.find_all_recursive_with(Path::new("loop"), &options);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(found, vec![dir.join("visible/target.txt")]);
+    assert_eq!(not_followed, vec![dir.join("visible/loop")]);
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "regex")]
+
+use regex::Regex;
+use search_path::SearchPath;
+use std::path::PathBuf;
+
+fn make_plugin_search_path() -> SearchPath {
+    vec!["tests/plugins/a", "tests/plugins/b"].into()
+}
+
+#[test]
+fn find_regex_first() {
+    let search_path = make_plugin_search_path();
+    let regex = Regex::new(r"^git-(.*)$").unwrap();
+    let found = search_path.find_regex(&regex).unwrap();
+    assert_eq!(found.path(), &PathBuf::from("tests/plugins/a/git-log"));
+    assert_eq!(found.get(0), Some("git-log"));
+    assert_eq!(found.get(1), Some("log"));
+    assert_eq!(found.len(), 2);
+}
+
+#[test]
+fn find_all_regex_captures() {
+    let search_path = make_plugin_search_path();
+    let regex = Regex::new(r"^git-(?P<plugin>.*)$").unwrap();
+    let plugins: Vec<(PathBuf, String)> = search_path
+        .find_all_regex(&regex)
+        .into_iter()
+        .map(|m| (m.path().clone(), m.name("plugin").unwrap().to_string()))
+        .collect();
+    assert_eq!(
+        plugins,
+        vec![
+            (PathBuf::from("tests/plugins/a/git-log"), "log".to_string()),
+            (
+                PathBuf::from("tests/plugins/a/git-status"),
+                "status".to_string()
+            ),
+            (PathBuf::from("tests/plugins/b/git-lfs"), "lfs".to_string()),
+            (
+                PathBuf::from("tests/plugins/b/git-status"),
+                "status".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn find_regex_optional_group() {
+    let search_path = make_plugin_search_path();
+    let regex = Regex::new(r"^gitk(-.*)?$").unwrap();
+    let found = search_path.find_regex(&regex).unwrap();
+    assert_eq!(found.path(), &PathBuf::from("tests/plugins/a/gitk"));
+    assert_eq!(found.get(1), None);
+}
+
+#[test]
+fn find_regex_none() {
+    let search_path = make_plugin_search_path();
+    let regex = Regex::new(r"^svn-").unwrap();
+    assert!(search_path.find_regex(&regex).is_none());
+    assert!(search_path.find_all_regex(&regex).is_empty());
+}
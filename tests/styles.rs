@@ -0,0 +1,72 @@
+use search_path::{PathStyle, SearchPath};
+use std::path::PathBuf;
+
+const WINDOWS_PATH: &str = r#"C:\Windows;;"C:\Program Files;Tools\bin";D:\bin"#;
+
+#[test]
+fn parse_windows_style() {
+    let search_path = SearchPath::parse_with_style(WINDOWS_PATH, PathStyle::Windows);
+    assert_eq!(
+        Vec::<PathBuf>::from(search_path),
+        vec![
+            PathBuf::from(r"C:\Windows"),
+            PathBuf::from(r"C:\Program Files;Tools\bin"),
+            PathBuf::from(r"D:\bin"),
+        ]
+    );
+}
+
+#[test]
+fn parse_windows_style_partial_quotes() {
+    let search_path = SearchPath::parse_with_style(r#"C:\"a;b"\c;D:\"#, PathStyle::Windows);
+    assert_eq!(
+        Vec::<PathBuf>::from(search_path),
+        vec![PathBuf::from(r"C:\a;b\c"), PathBuf::from(r"D:\")]
+    );
+}
+
+#[test]
+fn parse_unix_style() {
+    let search_path = SearchPath::parse_with_style("/usr/bin:/bin;x", PathStyle::Unix);
+    assert_eq!(
+        Vec::<PathBuf>::from(search_path),
+        vec![PathBuf::from("/usr/bin"), PathBuf::from("/bin;x")]
+    );
+}
+
+#[test]
+fn display_windows_style() {
+    let search_path = SearchPath::parse_with_style(WINDOWS_PATH, PathStyle::Windows);
+    assert_eq!(
+        search_path
+            .display_with_style(PathStyle::Windows)
+            .to_string(),
+        r#"C:\Windows;"C:\Program Files;Tools\bin";D:\bin"#
+    );
+}
+
+#[test]
+fn display_unix_style() {
+    let search_path: SearchPath = vec!["/usr/bin", "/bin"].into();
+    assert_eq!(
+        search_path.display_with_style(PathStyle::Unix).to_string(),
+        "/usr/bin:/bin"
+    );
+    assert_eq!(
+        search_path
+            .display_with_style(PathStyle::Windows)
+            .to_string(),
+        "/usr/bin;/bin"
+    );
+}
+
+#[test]
+fn display_native_style() {
+    let search_path: SearchPath = vec!["a", "b"].into();
+    assert_eq!(
+        search_path.to_string(),
+        search_path
+            .display_with_style(PathStyle::native())
+            .to_string()
+    );
+}
@@ -0,0 +1,169 @@
+#![cfg(target_os = "linux")]
+
+use search_path::{CacheOptions, CachedSearchPath, Entry, SearchPath, WatchEvent};
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+use common::make_temp_dir;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn next(receiver: &Receiver<WatchEvent>) -> WatchEvent {
+    receiver.recv_timeout(TIMEOUT).unwrap()
+}
+
+#[test]
+fn watch_shadowing_events() {
+    let dir = make_temp_dir("watch-shadow");
+    let (first, second) = (dir.join("first"), dir.join("second"));
+    fs::create_dir_all(&first).unwrap();
+    fs::create_dir_all(&second).unwrap();
+    let cached = Arc::new(CachedSearchPath::new(
+        vec![first.clone(), second.clone()].into(),
+        CacheOptions::default().with_revalidate(false),
+    ));
+    assert_eq!(cached.find(Path::new("tool")), None);
+    let (guard, receiver) = cached.subscribe().unwrap();
+
+    fs::write(second.join("tool"), "").unwrap();
+    assert_eq!(
+        next(&receiver),
+        WatchEvent::Appeared {
+            path: second.join("tool"),
+            first_match: true,
+            shadowing: None
+        }
+    );
+    fs::write(first.join("tool"), "").unwrap();
+    let event = next(&receiver);
+    assert_eq!(
+        event,
+        WatchEvent::Appeared {
+            path: first.join("tool"),
+            first_match: true,
+            shadowing: Some(second.join("tool"))
+        }
+    );
+    assert_eq!(
+        event.to_string(),
+        format!(
+            "`tool` appeared in `{}`, now shadowing `{}`",
+            first.display(),
+            second.join("tool").display()
+        )
+    );
+    assert_eq!(cached.find(Path::new("tool")), Some(first.join("tool")));
+
+    fs::remove_file(first.join("tool")).unwrap();
+    assert_eq!(
+        next(&receiver),
+        WatchEvent::Disappeared {
+            path: first.join("tool"),
+            resolves_to: Some(second.join("tool"))
+        }
+    );
+    assert_eq!(cached.find(Path::new("tool")), Some(second.join("tool")));
+
+    fs::write(first.join("other"), "").unwrap();
+    let _ = next(&receiver);
+    fs::write(second.join("other"), "").unwrap();
+    let event = next(&receiver);
+    assert_eq!(
+        event,
+        WatchEvent::Appeared {
+            path: second.join("other"),
+            first_match: false,
+            shadowing: None
+        }
+    );
+    assert_eq!(
+        event.to_string(),
+        format!(
+            "`other` appeared in `{}`, shadowed by an earlier entry",
+            second.display()
+        )
+    );
+
+    drop(guard);
+    let disconnected = receiver.recv_timeout(TIMEOUT);
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(disconnected, Err(RecvTimeoutError::Disconnected));
+}
+
+#[test]
+fn watch_new_directory_below_recursive_entry() {
+    let dir = make_temp_dir("watch-recursive");
+    let search_path = SearchPath::from(vec![Entry::new(dir.clone()).with_recursive(true)]);
+    let cached = Arc::new(CachedSearchPath::new(
+        search_path,
+        CacheOptions::default().with_revalidate(false),
+    ));
+    let (_guard, receiver) = cached.subscribe().unwrap();
+
+    fs::create_dir(dir.join("sub")).unwrap();
+    let created = next(&receiver);
+    fs::write(dir.join("sub/tool"), "").unwrap();
+    let appeared = next(&receiver);
+    let found = cached.find(Path::new("tool"));
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        created,
+        WatchEvent::Appeared {
+            path: dir.join("sub"),
+            first_match: true,
+            shadowing: None
+        }
+    );
+    assert_eq!(
+        appeared,
+        WatchEvent::Appeared {
+            path: dir.join("sub/tool"),
+            first_match: true,
+            shadowing: None
+        }
+    );
+    assert_eq!(found, Some(dir.join("sub/tool")));
+}
+
+#[test]
+fn watch_recreated_entry() {
+    let dir = make_temp_dir("watch-recreated");
+    let (bin, staging) = (dir.join("bin"), dir.join("staging"));
+    fs::create_dir_all(&bin).unwrap();
+    let cached = Arc::new(CachedSearchPath::new(
+        vec![bin.clone()].into(),
+        CacheOptions::default().with_revalidate(false),
+    ));
+    let (_guard, receiver) = cached.subscribe().unwrap();
+
+    fs::remove_dir(&bin).unwrap();
+    fs::create_dir(&staging).unwrap();
+    fs::write(staging.join("tool"), "").unwrap();
+    fs::rename(&staging, &bin).unwrap();
+    let moved = next(&receiver);
+    fs::write(bin.join("other"), "").unwrap();
+    let created = next(&receiver);
+    let found = cached.find(Path::new("tool"));
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        moved,
+        WatchEvent::Appeared {
+            path: bin.join("tool"),
+            first_match: true,
+            shadowing: None
+        }
+    );
+    assert_eq!(
+        created,
+        WatchEvent::Appeared {
+            path: bin.join("other"),
+            first_match: true,
+            shadowing: None
+        }
+    );
+    assert_eq!(found, Some(bin.join("tool")));
+}